@@ -0,0 +1,315 @@
+use crate::*;
+
+/// Configures a [`GeneticAlgorithm`] one setting at a time - see
+/// [`GeneticAlgorithm::builder()`]:
+///
+/// ```
+/// # use lib_genetic_algorithm::*;
+/// let ga = GeneticAlgorithm::builder()
+///     .selection(TournamentSelection::new(3))
+///     .mutation(GaussianMutation::new(0.05, 0.2))
+///     .elitism(2)
+///     .max_generations(100)
+///     .build();
+/// ```
+pub struct GeneticAlgorithmBuilder<S> {
+    selection_method: S,
+    crossover_method: Box<dyn CrossoverMethod>,
+    crossover_probability: f32,
+    mutation_method: Box<dyn MutationMethod>,
+    elite_count: usize,
+    hall_of_fame: usize,
+    population_size: Option<usize>,
+    termination: Termination,
+}
+
+/// When a [`GeneticAlgorithm`] should stop, as reported by
+/// [`GeneticAlgorithm::is_finished()`] - as soon as any of the criteria is
+/// met. By default there are none, so it never stops on its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Termination {
+    /// Stop after this many generations
+    pub max_generations: Option<usize>,
+
+    /// Stop once somebody's at least this fit
+    pub target_fitness: Option<f32>,
+
+    /// Stop once the best fitness hasn't improved for this many
+    /// generations
+    pub max_stagnation: Option<usize>,
+}
+
+impl GeneticAlgorithm<RouletteWheelSelection> {
+    /// Starts with [`GeneticAlgorithmBuilder::new()`]'s defaults.
+    pub fn builder() -> GeneticAlgorithmBuilder<RouletteWheelSelection> {
+        GeneticAlgorithmBuilder::new()
+    }
+}
+
+impl GeneticAlgorithmBuilder<RouletteWheelSelection> {
+    /// Roulette wheel selection, uniform crossover and gaussian mutation
+    /// (chance = 0.01, coeff = 0.3), without elitism, hall of fame or
+    /// termination criteria.
+    pub fn new() -> Self {
+        Self {
+            selection_method: RouletteWheelSelection,
+            crossover_method: Box::new(UniformCrossover),
+            crossover_probability: 1.0,
+            mutation_method: Box::new(GaussianMutation::new(0.01, 0.3)),
+            elite_count: 0,
+            hall_of_fame: 0,
+            population_size: None,
+            termination: Termination::default(),
+        }
+    }
+}
+
+impl Default for GeneticAlgorithmBuilder<RouletteWheelSelection> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> GeneticAlgorithmBuilder<S>
+where
+    S: SelectionMethod,
+{
+    pub fn selection<S2>(self, selection_method: S2) -> GeneticAlgorithmBuilder<S2>
+    where
+        S2: SelectionMethod,
+    {
+        GeneticAlgorithmBuilder {
+            selection_method,
+            crossover_method: self.crossover_method,
+            crossover_probability: self.crossover_probability,
+            mutation_method: self.mutation_method,
+            elite_count: self.elite_count,
+            hall_of_fame: self.hall_of_fame,
+            population_size: self.population_size,
+            termination: self.termination,
+        }
+    }
+
+    pub fn crossover(mut self, crossover_method: impl CrossoverMethod + 'static) -> Self {
+        self.crossover_method = Box::new(crossover_method);
+        self
+    }
+
+    /// Probability of a child being bred by crossover; otherwise it's just
+    /// a (mutated) copy of its first parent:
+    /// - 1.0 = always crossover (default)
+    /// - 0.0 = never crossover, evolution relies on mutation alone
+    pub fn crossover_probability(mut self, probability: f32) -> Self {
+        assert!((0.0..=1.0).contains(&probability));
+
+        self.crossover_probability = probability;
+        self
+    }
+
+    pub fn mutation(mut self, mutation_method: impl MutationMethod + 'static) -> Self {
+        self.mutation_method = Box::new(mutation_method);
+        self
+    }
+
+    /// See [`GeneticAlgorithm::with_elitism()`].
+    pub fn elitism(mut self, count: usize) -> Self {
+        self.elite_count = count;
+        self
+    }
+
+    /// See [`GeneticAlgorithm::with_hall_of_fame()`].
+    pub fn hall_of_fame(mut self, capacity: usize) -> Self {
+        self.hall_of_fame = capacity;
+        self
+    }
+
+    /// Makes [`GeneticAlgorithm::evolve()`] return populations of given
+    /// size, no matter the size of the population it's given.
+    pub fn population_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "population must not be empty");
+
+        self.population_size = Some(size);
+        self
+    }
+
+    pub fn termination(mut self, termination: Termination) -> Self {
+        self.termination = termination;
+        self
+    }
+
+    pub fn max_generations(mut self, generations: usize) -> Self {
+        self.termination.max_generations = Some(generations);
+        self
+    }
+
+    pub fn target_fitness(mut self, fitness: f32) -> Self {
+        self.termination.target_fitness = Some(fitness);
+        self
+    }
+
+    pub fn max_stagnation(mut self, generations: usize) -> Self {
+        self.termination.max_stagnation = Some(generations);
+        self
+    }
+
+    pub fn build(self) -> GeneticAlgorithm<S> {
+        GeneticAlgorithm {
+            selection_method: self.selection_method,
+            crossover_method: self.crossover_method,
+            mutation_method: self.mutation_method,
+            crossover_probability: self.crossover_probability,
+            elite_count: self.elite_count,
+            hall_of_fame: HallOfFame::new(self.hall_of_fame),
+            population_size: self.population_size,
+            termination: self.termination,
+            generation: 0,
+            best_fitness: None,
+            stagnation: 0,
+            parent_fitness: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            TestIndividual::create(vec![0.0, 0.0, 0.0].into_iter().collect()),
+            TestIndividual::create(vec![1.0, 1.0, 1.0].into_iter().collect()),
+            TestIndividual::create(vec![1.0, 2.0, 1.0].into_iter().collect()),
+            TestIndividual::create(vec![1.0, 2.0, 4.0].into_iter().collect()),
+        ]
+    }
+
+    fn chromosomes(population: &[TestIndividual]) -> Vec<Vec<f32>> {
+        population
+            .iter()
+            .map(|individual| individual.chromosome().iter().copied().collect())
+            .collect()
+    }
+
+    #[test]
+    fn defaults_match_new() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.01, 0.3),
+        );
+        let (expected, _) = ga.evolve(&mut rng, &population());
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let (actual, _) = GeneticAlgorithm::builder()
+            .build()
+            .evolve(&mut rng, &population());
+
+        assert_eq!(chromosomes(&actual), chromosomes(&expected));
+    }
+
+    #[test]
+    fn population_size() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::builder()
+            .selection(TournamentSelection::new(2))
+            .elitism(1)
+            .population_size(7)
+            .build();
+
+        let (population, _) = ga.evolve(&mut rng, &population());
+
+        assert_eq!(population.len(), 7);
+        assert_eq!(population[0].fitness(), 7.0);
+
+        let mut ga = GeneticAlgorithm::builder().population_size(2).build();
+        let (population, _) = ga.evolve(&mut rng, &population);
+
+        assert_eq!(population.len(), 2);
+    }
+
+    #[test]
+    fn without_crossover() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::builder()
+            .crossover_probability(0.0)
+            .mutation(GaussianMutation::new(0.0, 0.0))
+            .build();
+
+        let population = population();
+        let (actual, _) = ga.evolve(&mut rng, &population);
+
+        // Every child is a copy of somebody
+        let expected = chromosomes(&population);
+
+        for child in chromosomes(&actual) {
+            assert!(expected.contains(&child), "{child:?} has no parent");
+        }
+    }
+
+    #[test]
+    fn max_generations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::builder().max_generations(3).build();
+        let mut population = population();
+        let mut generations = 0;
+
+        while !ga.is_finished() {
+            population = ga.evolve(&mut rng, &population).0;
+            generations += 1;
+        }
+
+        assert_eq!(generations, 3);
+    }
+
+    #[test]
+    fn target_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::builder().target_fitness(7.0).build();
+
+        assert!(!ga.is_finished());
+
+        ga.evolve(&mut rng, &population()[..3]);
+        assert!(!ga.is_finished());
+
+        ga.evolve(&mut rng, &population());
+        assert!(ga.is_finished());
+    }
+
+    #[test]
+    fn max_stagnation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::builder().max_stagnation(2).build();
+        let population = population();
+
+        ga.evolve(&mut rng, &population[..2]);
+        ga.evolve(&mut rng, &population[..2]);
+        assert!(!ga.is_finished());
+
+        // Improvement resets the counter
+        ga.evolve(&mut rng, &population);
+        ga.evolve(&mut rng, &population);
+        assert!(!ga.is_finished());
+
+        ga.evolve(&mut rng, &population);
+        assert!(ga.is_finished());
+    }
+
+    #[test]
+    fn without_termination() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::builder().build();
+
+        for _ in 0..10 {
+            ga.evolve(&mut rng, &population());
+        }
+
+        assert!(!ga.is_finished());
+    }
+}
@@ -0,0 +1,762 @@
+use crate::*;
+use std::{error, fmt};
+
+/// [`GeneticAlgorithm`]'s settings in a form that can be kept in a TOML or
+/// JSON file - e.g.:
+///
+/// ```toml
+/// elitism = 2
+/// crossover_probability = 0.9
+///
+/// [selection]
+/// method = "tournament"
+/// size = 3
+///
+/// [mutation]
+/// method = "gaussian"
+/// chance = 0.05
+/// coeff = 0.2
+///
+/// [termination]
+/// max_generations = 500
+/// ```
+///
+/// Missing settings take the same defaults as
+/// [`GeneticAlgorithmBuilder::new()`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GaConfig {
+    pub selection: SelectionConfig,
+    pub crossover: CrossoverConfig,
+    pub crossover_probability: f32,
+    pub mutation: MutationConfig,
+    pub elitism: usize,
+    pub hall_of_fame: usize,
+    pub population_size: Option<usize>,
+    pub termination: Termination,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SelectionConfig {
+    /// [`RouletteWheelSelection`]
+    RouletteWheel,
+
+    /// [`TournamentSelection`]
+    Tournament {
+        size: usize,
+
+        #[serde(default = "one")]
+        chance: f32,
+    },
+
+    /// [`RankSelection::linear()`]
+    LinearRank { pressure: f32 },
+
+    /// [`RankSelection::exponential()`]
+    ExponentialRank { base: f32 },
+
+    /// [`StochasticUniversalSampling`]
+    StochasticUniversalSampling,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CrossoverConfig {
+    /// [`UniformCrossover`]
+    Uniform,
+
+    /// [`SinglePointCrossover`]
+    SinglePoint,
+
+    /// [`KPointCrossover`]
+    KPoint { k: usize },
+
+    /// [`ArithmeticCrossover`]
+    Arithmetic { alpha: f32 },
+
+    /// [`BlxAlphaCrossover`]
+    BlxAlpha { alpha: f32 },
+
+    /// [`SimulatedBinaryCrossover`]
+    SimulatedBinary { eta: f32 },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MutationConfig {
+    /// [`GaussianMutation`]
+    Gaussian { chance: f32, coeff: f32 },
+
+    /// [`PolynomialMutation`]
+    Polynomial { chance: f32, eta: f32 },
+
+    /// [`NormalMutation`]
+    Normal { chance: f32, sigma: f32 },
+
+    /// [`ScheduledMutation`]
+    Scheduled { chance: Schedule, sigma: Schedule },
+
+    /// [`OneFifthRuleMutation`]
+    OneFifthRule {
+        chance: f32,
+        sigma: f32,
+
+        #[serde(default)]
+        factor: Option<f32>,
+    },
+
+    /// [`SelfAdaptiveMutation`]
+    SelfAdaptive {
+        initial_step: f32,
+
+        #[serde(default)]
+        min_step: Option<f32>,
+    },
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+impl Default for GaConfig {
+    fn default() -> Self {
+        Self {
+            selection: SelectionConfig::RouletteWheel,
+            crossover: CrossoverConfig::Uniform,
+            crossover_probability: 1.0,
+            mutation: MutationConfig::Gaussian {
+                chance: 0.01,
+                coeff: 0.3,
+            },
+            elitism: 0,
+            hall_of_fame: 0,
+            population_size: None,
+            termination: Termination::default(),
+        }
+    }
+}
+
+impl GaConfig {
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(toml).map_err(ConfigError::Toml)?;
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(json).map_err(ConfigError::Json)?;
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Checks the values [`Self::builder()`] would otherwise panic on.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let probability = |name: &str, value: f32| {
+            if (0.0..=1.0).contains(&value) {
+                Ok(())
+            } else {
+                Err(ConfigError::Invalid(format!(
+                    "{name} must be between 0.0 and 1.0, got {value}"
+                )))
+            }
+        };
+
+        let distribution_index = |eta: f32| {
+            if eta >= 0.0 {
+                Ok(())
+            } else {
+                Err(ConfigError::Invalid(format!(
+                    "distribution index must not be negative, got {eta}"
+                )))
+            }
+        };
+
+        let positive = |name: &str, value: f32| {
+            if value > 0.0 {
+                Ok(())
+            } else {
+                Err(ConfigError::Invalid(format!(
+                    "{name} must be positive, got {value}"
+                )))
+            }
+        };
+
+        let non_negative = |name: &str, value: f32| {
+            if value >= 0.0 {
+                Ok(())
+            } else {
+                Err(ConfigError::Invalid(format!(
+                    "{name} must not be negative, got {value}"
+                )))
+            }
+        };
+
+        let schedule = |name: &str, schedule: &Schedule| {
+            let finite = |field: &str, value: f32| {
+                if value.is_finite() {
+                    Ok(())
+                } else {
+                    Err(ConfigError::Invalid(format!(
+                        "{name} {field} must be finite, got {value}"
+                    )))
+                }
+            };
+
+            match *schedule {
+                Schedule::Constant { value } => finite("value", value),
+
+                Schedule::Linear { start, end, .. } => {
+                    finite("start", start)?;
+                    finite("end", end)
+                }
+
+                Schedule::Exponential { start, decay, min } => {
+                    finite("start", start)?;
+                    finite("decay", decay)?;
+                    finite("min", min)?;
+                    positive(&format!("{name} decay"), decay)
+                }
+            }
+        };
+
+        match self.selection {
+            SelectionConfig::Tournament { size, chance } => {
+                if size == 0 {
+                    return Err(ConfigError::Invalid(
+                        "tournament must have at least one contestant".into(),
+                    ));
+                }
+
+                probability("tournament chance", chance)?;
+            }
+
+            SelectionConfig::LinearRank { pressure } if !(1.0..=2.0).contains(&pressure) => {
+                return Err(ConfigError::Invalid(format!(
+                    "rank pressure must be between 1.0 and 2.0, got {pressure}"
+                )));
+            }
+
+            SelectionConfig::ExponentialRank { base } if !(base > 0.0 && base <= 1.0) => {
+                return Err(ConfigError::Invalid(format!(
+                    "rank base must be greater than 0.0 and at most 1.0, got {base}"
+                )));
+            }
+
+            _ => (),
+        }
+
+        match &self.crossover {
+            CrossoverConfig::KPoint { k: 0 } => {
+                return Err(ConfigError::Invalid(
+                    "crossover needs at least one cut point".into(),
+                ));
+            }
+
+            CrossoverConfig::Arithmetic { alpha } => probability("crossover alpha", *alpha)?,
+
+            CrossoverConfig::BlxAlpha { alpha } if *alpha < 0.0 => {
+                return Err(ConfigError::Invalid(format!(
+                    "crossover alpha must not be negative, got {alpha}"
+                )));
+            }
+
+            CrossoverConfig::SimulatedBinary { eta } => distribution_index(*eta)?,
+
+            _ => (),
+        }
+
+        probability("crossover probability", self.crossover_probability)?;
+
+        match &self.mutation {
+            MutationConfig::Gaussian { chance, .. } => probability("mutation chance", *chance)?,
+
+            MutationConfig::Polynomial { chance, eta } => {
+                probability("mutation chance", *chance)?;
+                distribution_index(*eta)?;
+            }
+
+            MutationConfig::Normal { chance, sigma } => {
+                probability("mutation chance", *chance)?;
+                non_negative("mutation sigma", *sigma)?;
+            }
+
+            MutationConfig::Scheduled { chance, sigma } => {
+                schedule("mutation chance", chance)?;
+                schedule("mutation sigma", sigma)?;
+            }
+
+            MutationConfig::OneFifthRule {
+                chance,
+                sigma,
+                factor,
+            } => {
+                probability("mutation chance", *chance)?;
+                positive("mutation sigma", *sigma)?;
+
+                if let Some(factor) = factor {
+                    if !(*factor > 0.0 && *factor < 1.0) {
+                        return Err(ConfigError::Invalid(format!(
+                            "mutation factor must be between 0.0 and 1.0, got {factor}"
+                        )));
+                    }
+                }
+            }
+
+            MutationConfig::SelfAdaptive {
+                initial_step,
+                min_step,
+            } => {
+                positive("initial step", *initial_step)?;
+
+                if let Some(min_step) = min_step {
+                    non_negative("min step", *min_step)?;
+                }
+            }
+        }
+
+        if self.population_size == Some(0) {
+            return Err(ConfigError::Invalid("population must not be empty".into()));
+        }
+
+        Ok(())
+    }
+
+    /// Panics if the config is invalid - see [`Self::validate()`].
+    pub fn builder(&self) -> GeneticAlgorithmBuilder<SelectionConfig> {
+        self.validate().unwrap_or_else(|err| panic!("{err}"));
+
+        let builder = GeneticAlgorithmBuilder::new()
+            .selection(self.selection.clone())
+            .crossover_probability(self.crossover_probability)
+            .elitism(self.elitism)
+            .hall_of_fame(self.hall_of_fame)
+            .termination(self.termination);
+
+        let builder = match &self.crossover {
+            CrossoverConfig::Uniform => builder.crossover(UniformCrossover),
+            CrossoverConfig::SinglePoint => builder.crossover(SinglePointCrossover),
+            CrossoverConfig::KPoint { k } => builder.crossover(KPointCrossover::new(*k)),
+            CrossoverConfig::Arithmetic { alpha } => {
+                builder.crossover(ArithmeticCrossover::new(*alpha))
+            }
+            CrossoverConfig::BlxAlpha { alpha } => {
+                builder.crossover(BlxAlphaCrossover::new(*alpha))
+            }
+            CrossoverConfig::SimulatedBinary { eta } => {
+                builder.crossover(SimulatedBinaryCrossover::new(*eta))
+            }
+        };
+
+        let builder = match &self.mutation {
+            MutationConfig::Gaussian { chance, coeff } => {
+                builder.mutation(GaussianMutation::new(*chance, *coeff))
+            }
+
+            MutationConfig::Polynomial { chance, eta } => {
+                builder.mutation(PolynomialMutation::new(*chance, *eta))
+            }
+
+            MutationConfig::Normal { chance, sigma } => {
+                builder.mutation(NormalMutation::new(*chance, *sigma))
+            }
+
+            MutationConfig::Scheduled { chance, sigma } => {
+                builder.mutation(ScheduledMutation::new(chance.clone(), sigma.clone()))
+            }
+
+            MutationConfig::OneFifthRule {
+                chance,
+                sigma,
+                factor,
+            } => {
+                let method = OneFifthRuleMutation::new(*chance, *sigma);
+
+                match factor {
+                    Some(factor) => builder.mutation(method.with_factor(*factor)),
+                    None => builder.mutation(method),
+                }
+            }
+
+            MutationConfig::SelfAdaptive {
+                initial_step,
+                min_step,
+            } => {
+                let method = SelfAdaptiveMutation::new(*initial_step);
+
+                match min_step {
+                    Some(min_step) => builder.mutation(method.with_min_step(*min_step)),
+                    None => builder.mutation(method),
+                }
+            }
+        };
+
+        match self.population_size {
+            Some(size) => builder.population_size(size),
+            None => builder,
+        }
+    }
+
+    /// Panics if the config is invalid - see [`Self::validate()`].
+    pub fn build(&self) -> GeneticAlgorithm<SelectionConfig> {
+        self.builder().build()
+    }
+}
+
+impl SelectionMethod for SelectionConfig {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        match *self {
+            Self::RouletteWheel => RouletteWheelSelection.select(rng, population),
+            Self::Tournament { size, chance } => TournamentSelection::new(size)
+                .with_chance(chance)
+                .select(rng, population),
+            Self::LinearRank { pressure } => {
+                RankSelection::linear(pressure).select(rng, population)
+            }
+            Self::ExponentialRank { base } => {
+                RankSelection::exponential(base).select(rng, population)
+            }
+            Self::StochasticUniversalSampling => {
+                StochasticUniversalSampling.select(rng, population)
+            }
+        }
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        match *self {
+            Self::RouletteWheel => RouletteWheelSelection.select_many(rng, population, count),
+            Self::Tournament { size, chance } => TournamentSelection::new(size)
+                .with_chance(chance)
+                .select_many(rng, population, count),
+            Self::LinearRank { pressure } => {
+                RankSelection::linear(pressure).select_many(rng, population, count)
+            }
+            Self::ExponentialRank { base } => {
+                RankSelection::exponential(base).select_many(rng, population, count)
+            }
+            Self::StochasticUniversalSampling => {
+                StochasticUniversalSampling.select_many(rng, population, count)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+
+    /// Config parsed fine, but some of its values are out of range
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Toml(err) => write!(f, "malformed config toml: {err}"),
+            Self::Json(err) => write!(f, "malformed config json: {err}"),
+            Self::Invalid(msg) => write!(f, "invalid config: {msg}"),
+        }
+    }
+}
+
+impl error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Toml(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::Invalid(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn config() -> GaConfig {
+        GaConfig {
+            selection: SelectionConfig::Tournament {
+                size: 3,
+                chance: 1.0,
+            },
+            crossover_probability: 0.9,
+            mutation: MutationConfig::Gaussian {
+                chance: 0.05,
+                coeff: 0.2,
+            },
+            elitism: 2,
+            termination: Termination {
+                max_generations: Some(500),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_toml() {
+        let toml = r#"
+            elitism = 2
+            crossover_probability = 0.9
+
+            [selection]
+            method = "tournament"
+            size = 3
+
+            [mutation]
+            method = "gaussian"
+            chance = 0.05
+            coeff = 0.2
+
+            [termination]
+            max_generations = 500
+        "#;
+
+        assert_eq!(GaConfig::from_toml(toml).unwrap(), config());
+    }
+
+    #[test]
+    fn from_json() {
+        let json = r#"{
+            "selection": { "method": "tournament", "size": 3 },
+            "crossover_probability": 0.9,
+            "mutation": { "method": "gaussian", "chance": 0.05, "coeff": 0.2 },
+            "elitism": 2,
+            "termination": { "max_generations": 500 }
+        }"#;
+
+        assert_eq!(GaConfig::from_json(json).unwrap(), config());
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = serde_json::to_string(&config()).unwrap();
+        assert_eq!(GaConfig::from_json(&json).unwrap(), config());
+
+        let toml = toml::to_string(&config()).unwrap();
+        assert_eq!(GaConfig::from_toml(&toml).unwrap(), config());
+    }
+
+    #[test]
+    fn crossover() {
+        let toml = r#"
+            [crossover]
+            method = "blx_alpha"
+            alpha = 0.5
+        "#;
+
+        assert_eq!(
+            GaConfig::from_toml(toml).unwrap().crossover,
+            CrossoverConfig::BlxAlpha { alpha: 0.5 }
+        );
+
+        let json = r#"{ "crossover": { "method": "single_point" } }"#;
+
+        assert_eq!(
+            GaConfig::from_json(json).unwrap().crossover,
+            CrossoverConfig::SinglePoint
+        );
+    }
+
+    #[test]
+    fn simulated_binary_and_polynomial() {
+        let toml = r#"
+            [crossover]
+            method = "simulated_binary"
+            eta = 15.0
+
+            [mutation]
+            method = "polynomial"
+            chance = 0.1
+            eta = 20.0
+        "#;
+
+        let config = GaConfig::from_toml(toml).unwrap();
+
+        assert_eq!(
+            config.crossover,
+            CrossoverConfig::SimulatedBinary { eta: 15.0 }
+        );
+
+        assert_eq!(
+            config.mutation,
+            MutationConfig::Polynomial {
+                chance: 0.1,
+                eta: 20.0,
+            }
+        );
+
+        // Bounds belong to chromosomes, not to methods
+        let json = r#"{
+            "mutation": { "method": "polynomial", "chance": 0.1, "eta": 20.0, "bounds": [[0.0, 1.0]] }
+        }"#;
+
+        assert!(matches!(
+            GaConfig::from_json(json),
+            Err(ConfigError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn mutation() {
+        let toml = r#"
+            [mutation]
+            method = "scheduled"
+            chance = { kind = "constant", value = 0.1 }
+            sigma = { kind = "exponential", start = 0.5, decay = 0.99 }
+        "#;
+
+        assert_eq!(
+            GaConfig::from_toml(toml).unwrap().mutation,
+            MutationConfig::Scheduled {
+                chance: Schedule::Constant { value: 0.1 },
+                sigma: Schedule::Exponential {
+                    start: 0.5,
+                    decay: 0.99,
+                    min: 0.0,
+                },
+            }
+        );
+
+        let json = r#"{ "mutation": { "method": "one_fifth_rule", "chance": 0.5, "sigma": 0.1 } }"#;
+
+        assert_eq!(
+            GaConfig::from_json(json).unwrap().mutation,
+            MutationConfig::OneFifthRule {
+                chance: 0.5,
+                sigma: 0.1,
+                factor: None,
+            }
+        );
+
+        let json = r#"{ "mutation": { "method": "self_adaptive", "initial_step": 0.0 } }"#;
+
+        assert!(matches!(
+            GaConfig::from_json(json),
+            Err(ConfigError::Invalid(_))
+        ));
+
+        let toml = r#"
+            [mutation]
+            method = "scheduled"
+            chance = { kind = "constant", value = nan }
+            sigma = { kind = "constant", value = 0.1 }
+        "#;
+
+        assert_eq!(
+            GaConfig::from_toml(toml).unwrap_err().to_string(),
+            "invalid config: mutation chance value must be finite, got NaN"
+        );
+
+        let toml = r#"
+            [mutation]
+            method = "scheduled"
+            chance = { kind = "constant", value = 0.1 }
+            sigma = { kind = "exponential", start = 0.5, decay = 0.0 }
+        "#;
+
+        assert_eq!(
+            GaConfig::from_toml(toml).unwrap_err().to_string(),
+            "invalid config: mutation sigma decay must be positive, got 0"
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(GaConfig::from_toml("").unwrap(), GaConfig::default());
+        assert_eq!(GaConfig::from_json("{}").unwrap(), GaConfig::default());
+    }
+
+    #[test]
+    fn malformed() {
+        let err = GaConfig::from_toml("elitism = \"two\"").unwrap_err();
+        assert!(matches!(err, ConfigError::Toml(_)), "{err:?}");
+
+        let err = GaConfig::from_toml("elitsm = 2").unwrap_err();
+        assert!(matches!(err, ConfigError::Toml(_)), "{err:?}");
+
+        let err = GaConfig::from_json(r#"{ "selection": { "method": "lottery" } }"#).unwrap_err();
+        assert!(matches!(err, ConfigError::Json(_)), "{err:?}");
+    }
+
+    #[test]
+    fn invalid() {
+        let err = GaConfig::from_toml("crossover_probability = 1.5").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid config: crossover probability must be between 0.0 and 1.0, got 1.5"
+        );
+
+        let toml = r#"
+            [selection]
+            method = "linear_rank"
+            pressure = 3.0
+        "#;
+
+        assert!(matches!(
+            GaConfig::from_toml(toml),
+            Err(ConfigError::Invalid(_))
+        ));
+
+        let json = r#"{ "selection": { "method": "tournament", "size": 0 } }"#;
+
+        assert!(matches!(
+            GaConfig::from_json(json),
+            Err(ConfigError::Invalid(_))
+        ));
+
+        let json = r#"{ "crossover": { "method": "k_point", "k": 0 } }"#;
+
+        assert!(matches!(
+            GaConfig::from_json(json),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn build() {
+        let population: Vec<_> = (0..4)
+            .map(|n| TestIndividual::create(vec![n as f32; 3].into_iter().collect()))
+            .collect();
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = config().build();
+        let (actual, _) = ga.evolve(&mut rng, &population);
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::builder()
+            .selection(TournamentSelection::new(3))
+            .crossover_probability(0.9)
+            .mutation(GaussianMutation::new(0.05, 0.2))
+            .elitism(2)
+            .max_generations(500)
+            .build();
+        let (expected, _) = ga.evolve(&mut rng, &population);
+
+        let fitness = |population: &[TestIndividual]| -> Vec<f32> {
+            population.iter().map(|i| i.fitness()).collect()
+        };
+
+        assert_eq!(fitness(&actual), fitness(&expected));
+    }
+}
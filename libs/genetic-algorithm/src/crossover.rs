@@ -0,0 +1,546 @@
+use crate::*;
+
+/// Cuts both parents at the same random point and glues the beginning of
+/// one to the end of the other.
+///
+/// Unlike [`UniformCrossover`], genes lying next to each other (e.g. weights
+/// of the same neuron) are likely to be inherited together.
+#[derive(Clone, Debug)]
+pub struct SinglePointCrossover;
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        KPointCrossover::new(1).crossover(rng, parent_a, parent_b)
+    }
+}
+
+/// Cuts both parents at the same `k` random points and takes the pieces
+/// alternately from one and the other, starting with `parent_a`.
+///
+/// Chromosomes shorter than `k + 1` genes get cut between every gene.
+#[derive(Clone, Debug)]
+pub struct KPointCrossover {
+    k: usize,
+}
+
+impl KPointCrossover {
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "crossover needs at least one cut point");
+
+        Self { k }
+    }
+}
+
+impl CrossoverMethod for KPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        if parent_a.len() < 2 {
+            return parent_a.clone();
+        }
+
+        // Cutting at `n` means gene `n` is the first one of a new piece
+        let cuts = parent_a.len() - 1;
+        let mut points = index::sample(rng, cuts, self.k.min(cuts)).into_vec();
+
+        points.sort_unstable();
+
+        let mut points = points.into_iter().map(|point| point + 1).peekable();
+        let mut from_a = true;
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(idx, (&a, &b))| {
+                if points.next_if_eq(&idx).is_some() {
+                    from_a = !from_a;
+                }
+
+                if from_a {
+                    a
+                } else {
+                    b
+                }
+            })
+            .collect()
+    }
+}
+
+/// Blends parents into a weighted average of them: `alpha * a + (1 - alpha)
+/// * b`, gene by gene.
+///
+/// Deterministic - all the variety comes from selection and mutation; with
+/// `alpha` of 0.5 both children of the same parents would be identical.
+#[derive(Clone, Debug)]
+pub struct ArithmeticCrossover {
+    /// Weight of `parent_a`:
+    /// - 1.0 = child is a copy of `parent_a`
+    /// - 0.5 = child is the average of both parents
+    /// - 0.0 = child is a copy of `parent_b`
+    alpha: f32,
+}
+
+impl ArithmeticCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!((0.0..=1.0).contains(&alpha));
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        _rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| self.alpha * a + (1.0 - self.alpha) * b)
+            .collect()
+    }
+}
+
+/// Blend crossover (BLX-α): each gene is drawn uniformly from the range
+/// spanned by the parents' genes, extended on both sides by `alpha` times
+/// its length.
+///
+/// Unlike [`ArithmeticCrossover`], children can land outside of their
+/// parents' range, so the population doesn't shrink towards its average.
+#[derive(Clone, Debug)]
+pub struct BlxAlphaCrossover {
+    /// How far past the parents children can reach:
+    /// - 0.0 = only between the parents
+    /// - 0.5 = the usual choice; the range keeps its spread on average
+    alpha: f32,
+}
+
+impl BlxAlphaCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlxAlphaCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let extent = self.alpha * (a - b).abs();
+                let min = a.min(b) - extent;
+                let max = a.max(b) + extent;
+
+                min + (max - min) * rng.gen::<f32>()
+            })
+            .collect()
+    }
+}
+
+/// Simulated binary crossover (SBX): spreads children around their parents
+/// the way single-point crossover of binary-encoded numbers would - close
+/// to them most of the time, rarely far away.
+///
+/// Each gene is taken from one of the two children SBX would produce, at
+/// random. If the parents have [`GeneBounds`] (the first parent's win),
+/// children never leave them.
+#[derive(Clone, Debug)]
+pub struct SimulatedBinaryCrossover {
+    /// Distribution index - how closely children resemble their parents:
+    /// - 0.0 = children can land far away
+    /// - 2.0 to 20.0 = the usual choices
+    eta: f32,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        assert!(eta >= 0.0);
+
+        Self { eta }
+    }
+
+    /// Spread factor for a random `u` - `alpha` is 2.0 without bounds and
+    /// less than that the closer the parents are to a bound.
+    fn beta(&self, u: f32, alpha: f32) -> f32 {
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        if u <= 1.0 / alpha {
+            (u * alpha).powf(exponent)
+        } else {
+            (1.0 / (2.0 - u * alpha)).powf(exponent)
+        }
+    }
+
+    /// How much of the spread distribution lies between `distance` (from
+    /// the nearer parent to a bound, relative to the parents' distance) and
+    /// infinity, the rest getting cut off by the bound.
+    fn alpha(&self, distance: f32) -> f32 {
+        2.0 - (1.0 + 2.0 * distance).powf(-(self.eta + 1.0))
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let bounds = parent_a.bounds().or_else(|| parent_b.bounds());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(idx, (&a, &b))| {
+                let u = rng.gen::<f32>();
+                let lower = rng.gen_bool(0.5);
+                let bounds = bounds.and_then(|bounds| bounds.get(idx));
+
+                // Parents can end up out of bounds thanks to other methods,
+                // which would leave the spread distribution undefined
+                let (a, b) = match bounds {
+                    Some((min, max)) => (a.clamp(min, max), b.clamp(min, max)),
+                    None => (a, b),
+                };
+
+                let (y1, y2) = (a.min(b), a.max(b));
+                let spread = y2 - y1;
+
+                if spread <= f32::EPSILON {
+                    return a;
+                }
+
+                let alpha = match bounds {
+                    Some((min, _)) if lower => self.alpha((y1 - min) / spread),
+                    Some((_, max)) => self.alpha((max - y2) / spread),
+                    None => 2.0,
+                };
+
+                let beta = self.beta(u, alpha);
+
+                let child = if lower {
+                    0.5 * (y1 + y2 - beta * spread)
+                } else {
+                    0.5 * (y1 + y2 + beta * spread)
+                };
+
+                match bounds {
+                    Some((min, max)) => child.clamp(min, max),
+                    None => child,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn parents() -> (Chromosome, Chromosome) {
+        let parent_a = (1..=100).map(|n| n as f32).collect();
+        let parent_b = (1..=100).map(|n| -n as f32).collect();
+
+        (parent_a, parent_b)
+    }
+
+    /// Crosses [`parents()`] `count` times.
+    fn children(method: impl CrossoverMethod, count: usize) -> Vec<Chromosome> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let (parent_a, parent_b) = parents();
+
+        (0..count)
+            .map(|_| method.crossover(&mut rng, &parent_a, &parent_b))
+            .collect()
+    }
+
+    /// Indices of genes at which the child switches from one parent to the
+    /// other.
+    fn cut_points(child: &Chromosome) -> Vec<usize> {
+        let genes: Vec<_> = child.iter().collect();
+
+        (1..genes.len())
+            .filter(|&idx| genes[idx].signum() != genes[idx - 1].signum())
+            .collect()
+    }
+
+    /// Where each gene of 100 children of [`parents()`] landed, relative to
+    /// its parents: 0.0 is `parent_b`'s gene, 1.0 `parent_a`'s one.
+    fn positions(method: impl CrossoverMethod) -> Vec<f32> {
+        let (parent_a, parent_b) = parents();
+
+        children(method, 100)
+            .iter()
+            .flat_map(|child| {
+                child
+                    .iter()
+                    .zip(parent_a.iter().zip(parent_b.iter()))
+                    .map(|(c, (a, b))| (c - b) / (a - b))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn mean(values: &[f32]) -> f32 {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+
+    mod single_point_crossover {
+        use super::*;
+
+        #[test]
+        fn crossover() {
+            let child = &children(SinglePointCrossover, 1)[0];
+
+            assert_eq!(cut_points(child), [19]);
+            assert_eq!(child[18], 19.0);
+            assert_eq!(child[19], -20.0);
+        }
+
+        #[test]
+        fn cut_points_are_uniform() {
+            let mut quarters = [0; 4];
+
+            for child in children(SinglePointCrossover, 1000) {
+                let cut_points = cut_points(&child);
+
+                assert_eq!(cut_points.len(), 1);
+                quarters[(cut_points[0] - 1) * 4 / 99] += 1;
+            }
+
+            // Expected: ~250 each
+            assert_eq!(quarters, [240, 249, 278, 233]);
+        }
+    }
+
+    mod k_point_crossover {
+        use super::*;
+
+        #[test]
+        fn crossover() {
+            let child = &children(KPointCrossover::new(3), 1)[0];
+
+            assert_eq!(cut_points(child), [19, 63, 83]);
+            assert_eq!(child[0], 1.0);
+            assert_eq!(child[99], -100.0);
+        }
+
+        #[test]
+        fn inherits_from_both_parents_equally() {
+            let children = children(KPointCrossover::new(3), 1000);
+
+            let genes_a = children
+                .iter()
+                .flat_map(|child| child.iter())
+                .filter(|gene| **gene > 0.0)
+                .count();
+
+            // Expected: ~50000 out of 100000
+            assert_eq!(genes_a, 50384);
+        }
+
+        #[test]
+        fn with_more_points_than_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = [1.0, 2.0, 3.0].into_iter().collect();
+            let parent_b = [-1.0, -2.0, -3.0].into_iter().collect();
+
+            let child = KPointCrossover::new(5).crossover(&mut rng, &parent_a, &parent_b);
+
+            assert_eq!(child, [1.0, -2.0, 3.0].into_iter().collect());
+        }
+
+        #[test]
+        #[should_panic(expected = "at least one cut point")]
+        fn with_zero_points() {
+            KPointCrossover::new(0);
+        }
+    }
+
+    mod arithmetic_crossover {
+        use super::*;
+
+        #[test]
+        fn crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = [1.0, 2.0, 3.0].into_iter().collect();
+            let parent_b = [3.0, 2.0, -1.0].into_iter().collect();
+
+            let child: Vec<_> = ArithmeticCrossover::new(0.25)
+                .crossover(&mut rng, &parent_a, &parent_b)
+                .into_iter()
+                .collect();
+
+            assert_relative_eq!(child.as_slice(), [2.5, 2.0, 0.0].as_ref());
+        }
+
+        #[test]
+        fn with_extreme_alpha() {
+            let (parent_a, parent_b) = parents();
+
+            assert_eq!(children(ArithmeticCrossover::new(1.0), 1)[0], parent_a);
+            assert_eq!(children(ArithmeticCrossover::new(0.0), 1)[0], parent_b);
+        }
+    }
+
+    mod blx_alpha_crossover {
+        use super::*;
+
+        fn positions(alpha: f32) -> Vec<f32> {
+            super::positions(BlxAlphaCrossover::new(alpha))
+        }
+
+        #[test]
+        fn crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = [1.0, 2.0, 3.0].into_iter().collect();
+            let parent_b = [3.0, 2.0, -1.0].into_iter().collect();
+
+            let child: Vec<_> = BlxAlphaCrossover::new(0.5)
+                .crossover(&mut rng, &parent_a, &parent_b)
+                .into_iter()
+                .collect();
+
+            assert_relative_eq!(child.as_slice(), [0.7489624, 2.0, 4.2725043].as_ref());
+        }
+
+        #[test]
+        fn without_alpha() {
+            let positions = positions(0.0);
+
+            assert!(positions.iter().all(|p| (0.0..=1.0).contains(p)));
+            assert_relative_eq!(mean(&positions), 0.5, epsilon = 0.01);
+        }
+
+        #[test]
+        fn with_alpha() {
+            let positions = positions(0.5);
+
+            assert!(positions.iter().all(|p| (-0.5..=1.5).contains(p)));
+            assert_relative_eq!(mean(&positions), 0.5, epsilon = 0.02);
+
+            // A quarter of the genes on each side lands outside of the
+            // parents' range
+            let below = positions.iter().filter(|&&p| p < 0.0).count();
+            let above = positions.iter().filter(|&&p| p > 1.0).count();
+
+            assert_eq!((below, above), (2491, 2438));
+        }
+    }
+
+    mod simulated_binary_crossover {
+        use super::*;
+
+        #[test]
+        fn crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = [1.0, 2.0, 3.0].into_iter().collect();
+            let parent_b = [3.0, 2.0, -1.0].into_iter().collect();
+
+            let child: Vec<_> = SimulatedBinaryCrossover::new(2.0)
+                .crossover(&mut rng, &parent_a, &parent_b)
+                .into_iter()
+                .collect();
+
+            assert_relative_eq!(child.as_slice(), [2.720792, 2.0, -1.0485077].as_ref());
+        }
+
+        /// How many of 10000 genes landed within a quarter of the parents'
+        /// distance from either parent.
+        fn near_parents(eta: f32) -> usize {
+            positions(SimulatedBinaryCrossover::new(eta))
+                .into_iter()
+                .filter(|p| p.abs() < 0.25 || (p - 1.0).abs() < 0.25)
+                .count()
+        }
+
+        #[test]
+        fn stays_close_to_parents() {
+            let positions = positions(SimulatedBinaryCrossover::new(2.0));
+
+            assert_relative_eq!(mean(&positions), 0.5, epsilon = 0.02);
+
+            // Larger distribution index keeps children closer to either
+            // parent
+            assert_eq!(near_parents(0.0), 4153);
+            assert_eq!(near_parents(2.0), 7934);
+            assert_eq!(near_parents(20.0), 9999);
+        }
+
+        #[test]
+        fn with_bounds() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = SimulatedBinaryCrossover::new(0.0);
+            let bounds = GeneBounds::uniform(-1.0, 1.0);
+
+            let parent_a = Chromosome::from_iter([0.9, -0.9, 0.0, 0.5]).with_bounds(bounds);
+            let parent_b = [1.0, -0.8, 0.9, -0.5].into_iter().collect();
+
+            for _ in 0..1000 {
+                let child = method.crossover(&mut rng, &parent_a, &parent_b);
+
+                assert!(child.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+            }
+        }
+
+        #[test]
+        fn with_parents_out_of_bounds() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = SimulatedBinaryCrossover::new(2.0);
+            let bounds = GeneBounds::uniform(-1.0, 1.0);
+
+            let parent_a = Chromosome::from_iter([-3.0, 0.5, 2.0]).with_bounds(bounds);
+            let parent_b = [0.5, 5.0, 3.0].into_iter().collect();
+
+            for _ in 0..1000 {
+                let child = method.crossover(&mut rng, &parent_a, &parent_b);
+
+                assert!(child.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+            }
+        }
+
+        #[test]
+        fn with_identical_parents() {
+            let (parent_a, _) = parents();
+            let children = children(SimulatedBinaryCrossover::new(2.0), 1);
+
+            assert_ne!(children[0], parent_a);
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let child =
+                SimulatedBinaryCrossover::new(2.0).crossover(&mut rng, &parent_a, &parent_a);
+
+            assert_eq!(child, parent_a);
+        }
+    }
+}
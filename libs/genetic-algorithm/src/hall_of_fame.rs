@@ -0,0 +1,161 @@
+use crate::*;
+
+/// Best individuals ever seen, fittest first - unlike the population, it
+/// never forgets a good solution.
+///
+/// The same chromosome is listed only once, with the generation it was
+/// first seen in (elites, for instance, survive many generations
+/// unchanged).
+#[derive(Clone, Debug)]
+pub struct HallOfFame {
+    capacity: usize,
+    entries: Vec<HallOfFameEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HallOfFameEntry {
+    pub chromosome: Chromosome,
+    pub fitness: f32,
+
+    /// Generation the chromosome was seen in, starting from zero
+    pub generation: usize,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Entries sorted by fitness, fittest first; among equally fit
+    /// entries, ones found earlier come first.
+    pub fn entries(&self) -> &[HallOfFameEntry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<&HallOfFameEntry> {
+        self.entries.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inducts individuals of given generation that are fitter than the
+    /// ones already there.
+    pub fn record<I>(&mut self, population: &[I], generation: usize)
+    where
+        I: Individual,
+    {
+        if self.capacity == 0 {
+            return;
+        }
+
+        for individual in population {
+            let fitness = individual.fitness();
+
+            if self.is_full() && fitness <= self.entries[self.capacity - 1].fitness {
+                continue;
+            }
+
+            let chromosome = individual.chromosome();
+
+            let known = self
+                .entries
+                .iter()
+                .any(|entry| entry.chromosome.iter().eq(chromosome.iter()));
+
+            if known {
+                continue;
+            }
+
+            let idx = self
+                .entries
+                .partition_point(|entry| entry.fitness >= fitness);
+
+            self.entries.insert(
+                idx,
+                HallOfFameEntry {
+                    chromosome: chromosome.clone(),
+                    fitness,
+                    generation,
+                },
+            );
+
+            self.entries.truncate(self.capacity);
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.entries.len() >= self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().copied().collect())
+    }
+
+    fn summary(hall_of_fame: &HallOfFame) -> Vec<(f32, usize)> {
+        hall_of_fame
+            .entries()
+            .iter()
+            .map(|entry| (entry.fitness, entry.generation))
+            .collect()
+    }
+
+    #[test]
+    fn record() {
+        let mut hall_of_fame = HallOfFame::new(3);
+
+        hall_of_fame.record(&[individual(&[1.0]), individual(&[3.0])], 0);
+        assert_eq!(summary(&hall_of_fame), [(3.0, 0), (1.0, 0)]);
+
+        hall_of_fame.record(
+            &[individual(&[2.0]), individual(&[5.0]), individual(&[0.5])],
+            1,
+        );
+        assert_eq!(summary(&hall_of_fame), [(5.0, 1), (3.0, 0), (2.0, 1)]);
+
+        // Not good enough
+        hall_of_fame.record(&[individual(&[1.5]), individual(&[2.0, 0.0])], 2);
+        assert_eq!(summary(&hall_of_fame), [(5.0, 1), (3.0, 0), (2.0, 1)]);
+
+        assert_eq!(hall_of_fame.best().unwrap().chromosome[0], 5.0);
+    }
+
+    #[test]
+    fn record_skips_known_chromosomes() {
+        let mut hall_of_fame = HallOfFame::new(3);
+
+        hall_of_fame.record(&[individual(&[1.0, 2.0])], 0);
+        hall_of_fame.record(&[individual(&[1.0, 2.0]), individual(&[3.0])], 1);
+
+        assert_eq!(summary(&hall_of_fame), [(3.0, 0), (3.0, 1)]);
+        assert_eq!(hall_of_fame.entries()[0].chromosome.len(), 2);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut hall_of_fame = HallOfFame::new(0);
+
+        hall_of_fame.record(&[individual(&[1.0])], 0);
+
+        assert!(hall_of_fame.is_empty());
+        assert!(hall_of_fame.best().is_none());
+    }
+}
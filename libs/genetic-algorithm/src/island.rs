@@ -0,0 +1,320 @@
+use crate::*;
+
+/// Several [`GeneticAlgorithm`]s evolving separate sub-populations
+/// ("islands"), every now and then exchanging their best individuals.
+///
+/// Islands explore on their own and so drift towards different solutions,
+/// while migration keeps spreading the good ones - a single big population
+/// tends to converge on the first decent solution it finds instead.
+pub struct IslandModel<S> {
+    islands: Vec<GeneticAlgorithm<S>>,
+    topology: Topology,
+
+    /// Every how many generations individuals migrate
+    interval: usize,
+
+    /// How many individuals migrate to each island
+    migrants: usize,
+
+    generation: usize,
+}
+
+/// Which islands migrants come from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topology {
+    /// Each island receives migrants from the previous one, the first one
+    /// from the last one
+    Ring,
+
+    /// Each island receives the best individuals of all of the other
+    /// islands combined
+    FullyConnected,
+
+    /// Each island receives migrants from another island picked at random
+    /// every time
+    Random,
+}
+
+impl<S> IslandModel<S>
+where
+    S: SelectionMethod,
+{
+    /// Islands migrate along a [`Topology::Ring`], one individual every 10
+    /// generations by default.
+    pub fn new(islands: impl IntoIterator<Item = GeneticAlgorithm<S>>) -> Self {
+        let islands: Vec<_> = islands.into_iter().collect();
+
+        assert!(!islands.is_empty(), "model must have at least one island");
+
+        Self {
+            islands,
+            topology: Topology::Ring,
+            interval: 10,
+            migrants: 1,
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Makes `migrants` of the best individuals migrate to each island
+    /// every `interval` generations; they replace the newest children
+    /// there (never the elites).
+    pub fn with_migration(mut self, interval: usize, migrants: usize) -> Self {
+        assert!(interval > 0, "migration interval must be at least one");
+
+        self.interval = interval;
+        self.migrants = migrants;
+        self
+    }
+
+    pub fn islands(&self) -> &[GeneticAlgorithm<S>] {
+        &self.islands
+    }
+
+    /// Number of the generation [`Self::evolve()`] will get next, starting
+    /// from zero.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Evolves each island's population with its own algorithm, then - if
+    /// it's time - lets the best individuals of the given (already
+    /// evaluated) populations migrate into the new ones.
+    ///
+    /// Returns the new populations along with statistics of the given
+    /// ones, island by island.
+    pub fn evolve<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        populations: &[Vec<I>],
+    ) -> (Vec<Vec<I>>, Vec<Statistics>)
+    where
+        I: Individual,
+    {
+        assert_eq!(
+            populations.len(),
+            self.islands.len(),
+            "expected a population for each of {} islands",
+            self.islands.len()
+        );
+
+        let (mut new_populations, stats): (Vec<_>, Vec<_>) = self
+            .islands
+            .iter_mut()
+            .zip(populations)
+            .map(|(island, population)| island.evolve(rng, population))
+            .unzip();
+
+        self.generation += 1;
+
+        if self.generation % self.interval == 0 && self.islands.len() > 1 {
+            self.migrate(rng, populations, &mut new_populations);
+        }
+
+        (new_populations, stats)
+    }
+
+    fn migrate<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        populations: &[Vec<I>],
+        new_populations: &mut [Vec<I>],
+    ) where
+        I: Individual,
+    {
+        let n = populations.len();
+
+        for (dst, new_population) in new_populations.iter_mut().enumerate() {
+            let sources: Vec<_> = match self.topology {
+                Topology::Ring => vec![(dst + n - 1) % n],
+                Topology::FullyConnected => (0..n).filter(|&src| src != dst).collect(),
+                Topology::Random => {
+                    // Any island but `dst`
+                    let src = rng.gen_range(0..n - 1);
+
+                    vec![if src >= dst { src + 1 } else { src }]
+                }
+            };
+
+            let mut candidates: Vec<_> = sources
+                .into_iter()
+                .flat_map(|src| populations[src].iter())
+                .collect();
+
+            candidates.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+            let elites = self.islands[dst].elite_count.min(new_population.len());
+            let count = self
+                .migrants
+                .min(candidates.len())
+                .min(new_population.len() - elites);
+
+            new_population.truncate(new_population.len() - count);
+
+            new_population.extend(
+                candidates[..count]
+                    .iter()
+                    .map(|migrant| I::create(migrant.chromosome().clone())),
+            );
+        }
+    }
+}
+
+impl IslandModel<SelectionConfig> {
+    /// Given number of islands, each with an algorithm built from `config`.
+    pub fn from_config(config: &GaConfig, islands: usize) -> Self {
+        Self::new((0..islands).map(|_| config.build()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Islands whose individuals consist of a single gene - equal to the
+    /// island's number times 10, plus the individual's number.
+    fn populations(islands: usize, size: usize) -> Vec<Vec<TestIndividual>> {
+        (0..islands)
+            .map(|island| {
+                (0..size)
+                    .map(|n| {
+                        TestIndividual::create([(island * 10 + n) as f32].into_iter().collect())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn genes(populations: &[Vec<TestIndividual>]) -> Vec<Vec<i32>> {
+        populations
+            .iter()
+            .map(|population| {
+                population
+                    .iter()
+                    .map(|individual| individual.chromosome()[0] as i32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Islands that keep their best individual and fill the rest with
+    /// copies of individuals picked by roulette - so that new genes can
+    /// come only from migration.
+    fn model(islands: usize) -> IslandModel<RouletteWheelSelection> {
+        IslandModel::new((0..islands).map(|_| {
+            GeneticAlgorithm::builder()
+                .selection(RouletteWheelSelection)
+                .crossover(ArithmeticCrossover::new(1.0))
+                .mutation(GaussianMutation::new(0.0, 0.0))
+                .elitism(1)
+                .build()
+        }))
+    }
+
+    #[test]
+    fn ring() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut model = model(3).with_migration(1, 2);
+
+        let (populations, stats) = model.evolve(&mut rng, &populations(3, 4));
+        let populations = genes(&populations);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[2].max_fitness, 23.0);
+
+        // Elite stays first, two best individuals of the previous island
+        // come last
+        assert_eq!(populations[0][0], 3);
+        assert_eq!(populations[0][2..], [23, 22]);
+        assert_eq!(populations[1][0], 13);
+        assert_eq!(populations[1][2..], [3, 2]);
+        assert_eq!(populations[2][0], 23);
+        assert_eq!(populations[2][2..], [13, 12]);
+    }
+
+    #[test]
+    fn fully_connected() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut model = model(3)
+            .with_topology(Topology::FullyConnected)
+            .with_migration(1, 2);
+
+        let populations = genes(&model.evolve(&mut rng, &populations(3, 4)).0);
+
+        assert_eq!(populations[0][2..], [23, 22]);
+        assert_eq!(populations[1][2..], [23, 22]);
+        assert_eq!(populations[2][2..], [13, 12]);
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut model = model(4)
+            .with_topology(Topology::Random)
+            .with_migration(1, 1);
+
+        for _ in 0..10 {
+            let populations = genes(&model.evolve(&mut rng, &populations(4, 3)).0);
+
+            for (island, population) in populations.iter().enumerate() {
+                let migrant = population[2];
+
+                // Best of another island
+                assert_eq!(migrant % 10, 2);
+                assert_ne!(migrant / 10, island as i32);
+            }
+        }
+    }
+
+    #[test]
+    fn migration_interval() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut model = model(2).with_migration(3, 1);
+        let mut populations = populations(2, 3);
+        let mut migrated = Vec::new();
+
+        for _ in 0..3 {
+            populations = model.evolve(&mut rng, &populations).0;
+
+            // Whether the first island has anybody from the second one
+            migrated.push(genes(&populations)[0].iter().any(|&gene| gene >= 10));
+        }
+
+        assert_eq!(migrated, [false, false, true]);
+        assert_eq!(model.generation(), 3);
+    }
+
+    #[test]
+    fn single_island() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut model = model(1).with_migration(1, 5);
+
+        let (populations, _) = model.evolve(&mut rng, &populations(1, 4));
+
+        assert_eq!(populations[0].len(), 4);
+        assert!(genes(&populations)[0].iter().all(|&gene| gene < 10));
+    }
+
+    #[test]
+    fn from_config() {
+        let model = IslandModel::from_config(&GaConfig::default(), 4);
+
+        assert_eq!(model.islands().len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one island")]
+    fn without_islands() {
+        IslandModel::<RouletteWheelSelection>::new([]);
+    }
+}
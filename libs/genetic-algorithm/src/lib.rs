@@ -1,11 +1,58 @@
-use rand::seq::SliceRandom;
+mod builder;
+mod config;
+mod crossover;
+mod hall_of_fame;
+mod island;
+mod mutation;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod selection;
+mod statistics;
+
+pub use self::{
+    builder::*, config::*, crossover::*, hall_of_fame::*, island::*, mutation::*, selection::*,
+    statistics::*,
+};
+use rand::distributions::{Distribution, WeightedError, WeightedIndex};
+use rand::seq::{index, SliceRandom};
 use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
 use std::ops::Index;
 
 pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+
+    /// Probability of a child being bred by crossover; otherwise it's a
+    /// (mutated) copy of its first parent
+    crossover_probability: f32,
+
+    /// How many of the fittest individuals get copied into the next
+    /// generation unchanged
+    elite_count: usize,
+
+    hall_of_fame: HallOfFame,
+
+    /// Size of the populations [`Self::evolve()`] returns; by default the
+    /// same as the population it's given
+    population_size: Option<usize>,
+
+    termination: Termination,
+
+    /// How many times [`Self::evolve()`] has been called
+    generation: usize,
+
+    /// Highest fitness seen so far
+    best_fitness: Option<f32>,
+
+    /// For how many generations the highest fitness hasn't improved
+    stagnation: usize,
+
+    /// Fitness of the fitter parent of each individual of the population
+    /// returned by the last [`Self::evolve()`] (`None` for elites), for
+    /// telling how many of the children turned out better than them
+    parent_fitness: Vec<Option<f32>>,
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -17,35 +64,178 @@ where
         crossover_method: impl CrossoverMethod + 'static,
         mutation_method: impl MutationMethod + 'static,
     ) -> Self {
-        Self {
-            selection_method,
-            crossover_method: Box::new(crossover_method),
-            mutation_method: Box::new(mutation_method),
-        }
+        GeneticAlgorithmBuilder::new()
+            .selection(selection_method)
+            .crossover(crossover_method)
+            .mutation(mutation_method)
+            .build()
+    }
+
+    /// Copies `count` fittest individuals of each generation into the next
+    /// one unchanged, so that the best solution found so far can't get lost
+    /// to crossover or mutation.
+    pub fn with_elitism(mut self, count: usize) -> Self {
+        self.elite_count = count;
+        self
+    }
+
+    /// Keeps track of `capacity` best individuals ever seen by
+    /// [`Self::evolve()`] - see [`Self::hall_of_fame()`].
+    pub fn with_hall_of_fame(mut self, capacity: usize) -> Self {
+        self.hall_of_fame = HallOfFame::new(capacity);
+        self
+    }
+
+    pub fn hall_of_fame(&self) -> &HallOfFame {
+        &self.hall_of_fame
+    }
+
+    /// Number of the generation [`Self::evolve()`] will get next, starting
+    /// from zero.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Whether any of the termination criteria has been met (never, if
+    /// there are none) - e.g. `while !ga.is_finished() { ... }`.
+    pub fn is_finished(&self) -> bool {
+        let Termination {
+            max_generations,
+            target_fitness,
+            max_stagnation,
+        } = self.termination;
+
+        let reached = |target: f32| self.best_fitness.map_or(false, |best| best >= target);
+
+        max_generations.map_or(false, |max| self.generation >= max)
+            || target_fitness.map_or(false, reached)
+            || max_stagnation.map_or(false, |max| self.stagnation >= max)
+    }
+
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+    {
+        let (mut new_population, parents, stats) = self.prepare(rng, population);
+
+        new_population.extend(
+            parents
+                .chunks_exact(2)
+                .map(|parents| self.breed(rng, parents[0], parents[1])),
+        );
+
+        (new_population, stats)
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    /// Everything [`Self::evolve()`] does before breeding: returns the
+    /// elites, parents of the rest of the new population (in pairs) and
+    /// statistics of the current one.
+    fn prepare<'a, I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+    ) -> (Vec<I>, Vec<&'a I>, Statistics)
     where
         I: Individual,
     {
         assert!(!population.is_empty());
 
-        let new_population = (0..population.len())
-            .map(|_| {
-                let parent_a = self.selection_method.select(rng, population).chromosome();
-                let parent_b = self.selection_method.select(rng, population).chromosome();
+        let stats = Statistics::new(population);
 
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        if self
+            .best_fitness
+            .map_or(true, |best| stats.max_fitness > best)
+        {
+            self.best_fitness = Some(stats.max_fitness);
+            self.stagnation = 0;
+        } else {
+            self.stagnation += 1;
+        }
 
-                self.mutation_method.mutate(rng, &mut child);
+        self.hall_of_fame.record(population, self.generation);
 
-                I::create(child)
-            })
-            .collect();
+        self.mutation_method.adapt(&MutationFeedback {
+            generation: self.generation,
+            success_rate: self.success_rate(population),
+        });
 
-        let stats = Statistics::new(population);
+        self.generation += 1;
 
-        (new_population, stats)
+        let size = self.population_size.unwrap_or(population.len());
+        let elite_count = self.elite_count.min(size).min(population.len());
+        let mut elites = Vec::with_capacity(size);
+
+        if elite_count > 0 {
+            let mut ranked: Vec<_> = population.iter().collect();
+
+            ranked.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+            elites.extend(
+                ranked[..elite_count]
+                    .iter()
+                    .map(|individual| I::create(individual.chromosome().clone())),
+            );
+        }
+
+        let parents = self
+            .selection_method
+            .select_many(rng, population, 2 * (size - elite_count));
+
+        self.parent_fitness = vec![None; elite_count];
+
+        self.parent_fitness.extend(
+            parents
+                .chunks_exact(2)
+                .map(|parents| Some(parents[0].fitness().max(parents[1].fitness()))),
+        );
+
+        (elites, parents, stats)
+    }
+
+    fn breed<I>(&self, rng: &mut dyn RngCore, parent_a: &I, parent_b: &I) -> I
+    where
+        I: Individual,
+    {
+        let parent_a = parent_a.chromosome();
+        let parent_b = parent_b.chromosome();
+
+        let crossover =
+            self.crossover_probability >= 1.0 || rng.gen_bool(self.crossover_probability as f64);
+
+        let mut child = if crossover {
+            let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+
+            child.inherit(parent_a, parent_b);
+            child
+        } else {
+            parent_a.clone()
+        };
+
+        self.mutation_method.mutate(rng, &mut child);
+
+        I::create(child)
+    }
+
+    /// Fraction of the population (bred by the last [`Self::evolve()`])
+    /// that's fitter than its parents; `None` if it's not known - e.g. in
+    /// the first generation.
+    fn success_rate<I>(&self, population: &[I]) -> Option<f32>
+    where
+        I: Individual,
+    {
+        if self.parent_fitness.len() != population.len() {
+            return None;
+        }
+
+        let (children, successes) = population
+            .iter()
+            .zip(&self.parent_fitness)
+            .filter_map(|(child, parent)| parent.map(|parent| child.fitness() > parent))
+            .fold((0, 0), |(children, successes), success| {
+                (children + 1, successes + success as usize)
+            });
+
+        (children > 0).then(|| successes as f32 / children as f32)
     }
 }
 
@@ -59,8 +249,30 @@ pub trait SelectionMethod {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
         I: Individual;
+
+    /// Selects `count` individuals at once - e.g. the whole parent pool
+    /// of the next generation.
+    ///
+    /// By default that's just calling [`Self::select()`] `count` times;
+    /// methods can override it to do better (see
+    /// [`StochasticUniversalSampling`]) or to avoid repeating work shared
+    /// by all of the selections (see [`RankSelection`]).
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        (0..count).map(|_| self.select(rng, population)).collect()
+    }
 }
 
+/// Selects individuals with probability proportional to their fitness,
+/// which must not be negative; if everybody's fitness is zero, selects
+/// uniformly.
 #[derive(Debug)]
 pub struct RouletteWheelSelection;
 
@@ -69,22 +281,96 @@ impl SelectionMethod for RouletteWheelSelection {
     where
         I: Individual,
     {
-        population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("got empty population")
+        match population.choose_weighted(&mut *rng, |individual| individual.fitness()) {
+            Ok(individual) => individual,
+
+            // Nobody's better than anybody else
+            Err(WeightedError::AllWeightsZero) => population.choose(rng).unwrap(),
+
+            Err(WeightedError::NoItem) => panic!("got empty population"),
+            Err(err) => panic!("cannot select from population: {err}"),
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Chromosome {
     genes: Vec<f32>,
+
+    /// Mutation step size of each gene, for [`SelfAdaptiveMutation`]; empty
+    /// for chromosomes that don't carry any
+    step_sizes: Vec<f32>,
+
+    /// Range each gene is allowed to take, for methods that know how to
+    /// respect it
+    bounds: Option<GeneBounds>,
 }
 
 impl Chromosome {
+    /// Attaches a mutation step size to each gene - see
+    /// [`SelfAdaptiveMutation`].
+    pub fn with_step_sizes(mut self, step_sizes: impl IntoIterator<Item = f32>) -> Self {
+        self.step_sizes = step_sizes.into_iter().collect();
+
+        assert_eq!(
+            self.step_sizes.len(),
+            self.genes.len(),
+            "chromosome needs one step size per gene"
+        );
+
+        self
+    }
+
+    pub fn step_sizes(&self) -> &[f32] {
+        &self.step_sizes
+    }
+
+    pub(crate) fn step_sizes_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.step_sizes
+    }
+
+    /// Restricts genes to given range - see [`GeneBounds`].
+    ///
+    /// Bounds aren't enforced by the chromosome itself: methods that
+    /// respect them (e.g. [`SimulatedBinaryCrossover`] or
+    /// [`PolynomialMutation`]) keep genes within, others ignore them.
+    pub fn with_bounds(mut self, bounds: GeneBounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn bounds(&self) -> Option<&GeneBounds> {
+        self.bounds.as_ref()
+    }
+
+    /// Gives a child created by crossover (which knows only about genes)
+    /// its parents' bounds and the average of their step sizes.
+    fn inherit(&mut self, parent_a: &Chromosome, parent_b: &Chromosome) {
+        if self.bounds.is_none() {
+            self.bounds = parent_a.bounds.clone().or_else(|| parent_b.bounds.clone());
+        }
+
+        if !self.step_sizes.is_empty() {
+            return;
+        }
+
+        self.step_sizes = match (parent_a.step_sizes(), parent_b.step_sizes()) {
+            ([], []) => return,
+            (a, []) | ([], a) => a.to_vec(),
+            (a, b) => a.iter().zip(b).map(|(a, b)| (a + b) / 2.0).collect(),
+        };
+
+        self.step_sizes.truncate(self.genes.len());
+    }
+
     pub fn len(&self) -> usize {
         self.genes.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &f32> {
         self.genes.iter()
     }
@@ -106,6 +392,8 @@ impl FromIterator<f32> for Chromosome {
     fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
         Self {
             genes: iter.into_iter().collect(),
+            step_sizes: Vec::new(),
+            bounds: None,
         }
     }
 }
@@ -119,7 +407,95 @@ impl IntoIterator for Chromosome {
     }
 }
 
-pub trait CrossoverMethod {
+/// Range of values each gene of a [`Chromosome`] is allowed to take, for
+/// methods that know how to respect it (e.g. [`SimulatedBinaryCrossover`]
+/// or [`PolynomialMutation`]) - see [`Chromosome::with_bounds()`].
+///
+/// Serialized as either `{ min = -1.0, max = 1.0 }` or `[[-1.0, 1.0],
+/// [0.0, 2.0], ...]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged, try_from = "UncheckedGeneBounds")]
+pub enum GeneBounds {
+    /// Same range for every gene
+    Uniform { min: f32, max: f32 },
+
+    /// One `(min, max)` range per gene, in the chromosome's order; genes
+    /// past the end of the list are unbounded
+    PerGene(Vec<(f32, f32)>),
+}
+
+/// [`GeneBounds`] as deserialized, before checking they're valid.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UncheckedGeneBounds {
+    Uniform { min: f32, max: f32 },
+    PerGene(Vec<(f32, f32)>),
+}
+
+impl TryFrom<UncheckedGeneBounds> for GeneBounds {
+    type Error = &'static str;
+
+    fn try_from(bounds: UncheckedGeneBounds) -> Result<Self, Self::Error> {
+        let bounds = match bounds {
+            UncheckedGeneBounds::Uniform { min, max } => Self::Uniform { min, max },
+            UncheckedGeneBounds::PerGene(bounds) => Self::PerGene(bounds),
+        };
+
+        if bounds.is_valid() {
+            Ok(bounds)
+        } else {
+            Err(INVALID_GENE_BOUNDS)
+        }
+    }
+}
+
+const INVALID_GENE_BOUNDS: &str = "gene bounds must be finite, with min < max";
+
+impl GeneBounds {
+    pub fn uniform(min: f32, max: f32) -> Self {
+        let bounds = Self::Uniform { min, max };
+
+        assert!(bounds.is_valid(), "{INVALID_GENE_BOUNDS}");
+        bounds
+    }
+
+    pub fn per_gene(bounds: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let bounds = Self::PerGene(bounds.into_iter().collect());
+
+        assert!(bounds.is_valid(), "{INVALID_GENE_BOUNDS}");
+        bounds
+    }
+
+    /// Range of given gene; `None` if it's unbounded.
+    pub fn get(&self, gene: usize) -> Option<(f32, f32)> {
+        match self {
+            Self::Uniform { min, max } => Some((*min, *max)),
+            Self::PerGene(bounds) => bounds.get(gene).copied(),
+        }
+    }
+
+    /// Moves out-of-range genes to the nearest bound.
+    pub fn clamp(&self, chromosome: &mut Chromosome) {
+        for (idx, gene) in chromosome.iter_mut().enumerate() {
+            if let Some((min, max)) = self.get(idx) {
+                *gene = gene.clamp(min, max);
+            }
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let is_valid = |min: f32, max: f32| min.is_finite() && max.is_finite() && min < max;
+
+        match self {
+            Self::Uniform { min, max } => is_valid(*min, *max),
+            Self::PerGene(bounds) => bounds.iter().all(|&(min, max)| is_valid(min, max)),
+        }
+    }
+}
+
+/// `Send + Sync`, so that the algorithm can breed children on many threads
+/// (see the `parallel` feature).
+pub trait CrossoverMethod: Send + Sync {
     fn crossover(
         &self,
         rng: &mut dyn RngCore,
@@ -148,10 +524,34 @@ impl CrossoverMethod for UniformCrossover {
     }
 }
 
-pub trait MutationMethod {
+/// `Send + Sync` for the same reason as [`CrossoverMethod`].
+pub trait MutationMethod: Send + Sync {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+
+    /// Called by [`GeneticAlgorithm::evolve()`] once per generation, before
+    /// any children get mutated - lets methods adjust themselves to how the
+    /// evolution is going.
+    fn adapt(&mut self, _feedback: &MutationFeedback) {
+        //
+    }
 }
 
+/// How the evolution is going, as told to [`MutationMethod::adapt()`].
+#[derive(Clone, Debug)]
+pub struct MutationFeedback {
+    /// Generation about to be bred, starting from zero
+    pub generation: usize,
+
+    /// Fraction of the current population that's fitter than its parents;
+    /// `None` if it's not known - e.g. in the first generation, or when the
+    /// population isn't the one returned by the last
+    /// [`GeneticAlgorithm::evolve()`]
+    pub success_rate: Option<f32>,
+}
+
+/// Nudges random genes by amounts drawn uniformly from `-coeff..coeff` -
+/// despite its name; see [`NormalMutation`] for the normally distributed
+/// one.
 #[derive(Clone, Debug)]
 pub struct GaussianMutation {
     /// probability of changing a gene:
@@ -167,7 +567,7 @@ pub struct GaussianMutation {
 
 impl GaussianMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
+        assert!((0.0..=1.0).contains(&chance));
 
         Self { chance, coeff }
     }
@@ -185,40 +585,6 @@ impl MutationMethod for GaussianMutation {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct Statistics {
-    pub min_fitness: f32,
-    pub max_fitness: f32,
-    pub avg_fitness: f32,
-}
-
-impl Statistics {
-    pub fn new<I>(population: &[I]) -> Self
-    where
-        I: Individual,
-    {
-        assert!(!population.is_empty());
-
-        let mut min_fitness = population[0].fitness();
-        let mut max_fitness = min_fitness;
-        let mut sum_fitness = 0.0;
-
-        for individual in population {
-            let fitness = individual.fitness();
-
-            min_fitness = min_fitness.min(fitness);
-            max_fitness = max_fitness.max(fitness);
-            sum_fitness += fitness;
-        }
-
-        Self {
-            min_fitness,
-            max_fitness,
-            avg_fitness: sum_fitness / (population.len() as f32),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,6 +619,70 @@ mod tests {
         assert_eq!(actual_histogram, expected_histogram);
     }
 
+    #[test]
+    fn roulette_wheel_selection_with_zero_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = vec![TestIndividual::new(0.0); 4];
+
+        let actual = RouletteWheelSelection.select(&mut rng, &population);
+
+        assert_eq!(actual.fitness(), 0.0);
+    }
+
+    #[test]
+    fn gene_bounds() {
+        let mut chromosome: Chromosome = [-2.0, 0.5, 3.0].into_iter().collect();
+
+        GeneBounds::uniform(-1.0, 1.0).clamp(&mut chromosome);
+        assert_eq!(chromosome, [-1.0, 0.5, 1.0].into_iter().collect());
+
+        let bounds = GeneBounds::per_gene([(0.0, 1.0), (0.0, 0.25), (2.0, 4.0)]);
+
+        bounds.clamp(&mut chromosome);
+        assert_eq!(chromosome, [0.0, 0.25, 2.0].into_iter().collect());
+        assert_eq!(bounds.get(2), Some((2.0, 4.0)));
+    }
+
+    #[test]
+    fn gene_bounds_shorter_than_chromosome() {
+        let mut chromosome: Chromosome = [-2.0, 0.5, 3.0].into_iter().collect();
+        let bounds = GeneBounds::per_gene([(-1.0, 1.0)]);
+
+        bounds.clamp(&mut chromosome);
+        assert_eq!(chromosome, [-1.0, 0.5, 3.0].into_iter().collect());
+        assert_eq!(bounds.get(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "min < max")]
+    fn gene_bounds_with_empty_range() {
+        GeneBounds::per_gene([(0.0, 1.0), (1.0, 1.0)]);
+    }
+
+    #[test]
+    fn gene_bounds_serde() {
+        let bounds = GeneBounds::per_gene([(0.0, 1.0), (-1.0, 1.0)]);
+        let json = serde_json::to_string(&bounds).unwrap();
+
+        assert_eq!(json, "[[0.0,1.0],[-1.0,1.0]]");
+        assert_eq!(serde_json::from_str::<GeneBounds>(&json).unwrap(), bounds);
+
+        assert_eq!(
+            serde_json::from_str::<GeneBounds>(r#"{ "min": -1.0, "max": 1.0 }"#).unwrap(),
+            GeneBounds::uniform(-1.0, 1.0)
+        );
+
+        for json in [r#"{ "min": 1.0, "max": 0.0 }"#, "[[0.0, 1.0], [2.0, 2.0]]"] {
+            let err = serde_json::from_str::<GeneBounds>(json).unwrap_err();
+
+            assert!(err.to_string().contains("min < max"), "{err}");
+        }
+
+        let err = toml::from_str::<GeneBounds>("min = nan\nmax = 1.0").unwrap_err();
+
+        assert!(err.to_string().contains("finite"), "{err}");
+    }
+
     #[test]
     fn uniform_crossover() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
@@ -278,7 +708,7 @@ mod tests {
 
         let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RouletteWheelSelection,
             UniformCrossover,
             GaussianMutation::new(0.5, 0.5),
@@ -292,21 +722,224 @@ mod tests {
         ];
 
         for _ in 0..10 {
-            population = ga.evolve(&mut rng, &population);
+            (population, _) = ga.evolve(&mut rng, &population);
         }
 
         let expected_population = vec![
-            individual(&[0.4476949, 2.0648358, 4.3058133]),
-            individual(&[1.2126867, 1.5538777, 2.886911]),
-            individual(&[1.0617678, 2.265739, 4.428764]),
-            individual(&[0.95909685, 2.4618788, 4.024733]),
+            individual(&[1.1013001, 1.6719795, 3.2955947]),
+            individual(&[1.3124838, 1.70271, 3.2955947]),
+            individual(&[1.7598352, 1.70271, 3.3595667]),
+            individual(&[1.3124838, 0.9894245, 3.270933]),
         ];
 
         assert_eq!(population, expected_population);
     }
 
+    #[test]
+    fn elitism() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(1.0, 1.0),
+        )
+        .with_elitism(2);
+
+        let population: Vec<_> = [[0.0, 0.0], [4.0, 1.0], [1.0, 1.0], [2.0, 2.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().copied().collect()))
+            .collect();
+
+        let (new_population, _) = ga.evolve(&mut rng, &population);
+
+        assert_eq!(new_population.len(), 4);
+
+        // Two fittest individuals survive unchanged, everybody else is
+        // mutated
+        assert_eq!(new_population[0], population[1]);
+        assert_eq!(new_population[1], population[3]);
+        assert!(new_population[2..].iter().all(|i| !population.contains(i)));
+    }
+
+    #[test]
+    fn elitism_larger_than_population() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(1.0, 1.0),
+        )
+        .with_elitism(10);
+
+        let population = vec![TestIndividual::create([1.0].into_iter().collect())];
+        let (new_population, _) = ga.evolve(&mut rng, &population);
+
+        assert_eq!(new_population, population);
+    }
+
+    #[test]
+    fn hall_of_fame() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        )
+        .with_hall_of_fame(2);
+
+        let mut population: Vec<_> = [[0.0, 0.0], [1.0, 1.0], [1.0, 2.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().copied().collect()))
+            .collect();
+
+        let mut best = Vec::new();
+
+        for _ in 0..10 {
+            let fittest = population
+                .iter()
+                .map(|individual| individual.fitness())
+                .fold(f32::MIN, f32::max);
+
+            best.push(fittest);
+            (population, _) = ga.evolve(&mut rng, &population);
+        }
+
+        assert_eq!(ga.generation(), 10);
+
+        let hall_of_fame = ga.hall_of_fame().entries();
+
+        assert_eq!(hall_of_fame.len(), 2);
+        assert!(hall_of_fame[0].fitness >= hall_of_fame[1].fitness);
+
+        // Best entry is the best individual of all of the generations
+        let generation = hall_of_fame[0].generation;
+
+        assert_eq!(hall_of_fame[0].fitness, best[generation]);
+        assert!(best
+            .iter()
+            .all(|&fitness| fitness <= hall_of_fame[0].fitness));
+    }
+
+    #[test]
+    fn mutation_feedback() {
+        use std::sync::{Arc, Mutex};
+
+        /// Leaves children alone, remembering what it's been told
+        struct Recorder(Arc<Mutex<Vec<MutationFeedback>>>);
+
+        impl MutationMethod for Recorder {
+            fn mutate(&self, _rng: &mut dyn RngCore, _child: &mut Chromosome) {
+                //
+            }
+
+            fn adapt(&mut self, feedback: &MutationFeedback) {
+                self.0.lock().unwrap().push(feedback.clone());
+            }
+        }
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let feedback = Arc::new(Mutex::new(Vec::new()));
+
+        let mut ga = GeneticAlgorithm::builder()
+            .crossover(ArithmeticCrossover::new(0.5))
+            .mutation(Recorder(feedback.clone()))
+            .elitism(1)
+            .build();
+
+        let population: Vec<_> = [[0.0, 0.0], [1.0, 1.0], [1.0, 2.0], [2.0, 4.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().copied().collect()))
+            .collect();
+
+        let (population, _) = ga.evolve(&mut rng, &population);
+        let (population, _) = ga.evolve(&mut rng, &population);
+
+        // Not the population returned by the last call
+        ga.evolve(&mut rng, &population[1..]);
+
+        let actual: Vec<_> = feedback
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|feedback| (feedback.generation, feedback.success_rate))
+            .collect();
+
+        // Averages of two parents are never better than the better one
+        assert_eq!(actual, [(0, None), (1, Some(0.0)), (2, None)]);
+    }
+
+    #[test]
+    fn step_sizes_are_inherited() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::builder()
+            .crossover(UniformCrossover)
+            .mutation(GaussianMutation::new(0.0, 0.0))
+            .build();
+
+        let population: Vec<_> = [(1.0, 0.1), (2.0, 0.3)]
+            .iter()
+            .map(|&(gene, step)| {
+                let chromosome = Chromosome::from_iter([gene; 3]).with_step_sizes([step; 3]);
+
+                TestIndividual::create(chromosome)
+            })
+            .collect();
+
+        let (population, _) = ga.evolve(&mut rng, &population);
+
+        for individual in population {
+            for step in individual.chromosome().step_sizes() {
+                assert!([0.1, 0.2, 0.3]
+                    .iter()
+                    .any(|s| approx::relative_eq!(s, step)));
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_are_inherited() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut ga = GeneticAlgorithm::builder()
+            .selection(TournamentSelection::new(2))
+            .crossover(SimulatedBinaryCrossover::new(0.0))
+            .mutation(PolynomialMutation::new(1.0, 0.0))
+            .build();
+
+        let mut population: Vec<_> = [-0.9, 0.9]
+            .iter()
+            .map(|&gene| {
+                let chromosome =
+                    Chromosome::from_iter([gene; 3]).with_bounds(GeneBounds::uniform(-1.0, 1.0));
+
+                TestIndividual::create(chromosome)
+            })
+            .collect();
+
+        for _ in 0..10 {
+            population = ga.evolve(&mut rng, &population).0;
+        }
+
+        for individual in population {
+            let chromosome = individual.chromosome();
+
+            assert_eq!(chromosome.bounds(), Some(&GeneBounds::uniform(-1.0, 1.0)));
+            assert!(chromosome.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one step size per gene")]
+    fn step_sizes_of_wrong_length() {
+        Chromosome::from_iter([1.0, 2.0]).with_step_sizes([0.1]);
+    }
+
     #[derive(Clone, Debug, PartialEq)]
-    enum TestIndividual {
+    pub(crate) enum TestIndividual {
         /// For tests that require using chromosome
         WithCromosome { chromosome: Chromosome },
 
@@ -315,7 +948,7 @@ mod tests {
     }
 
     impl TestIndividual {
-        fn new(fitness: f32) -> Self {
+        pub(crate) fn new(fitness: f32) -> Self {
             Self::WithFitness { fitness }
         }
     }
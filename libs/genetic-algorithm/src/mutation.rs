@@ -0,0 +1,623 @@
+use crate::*;
+use rand_distr::StandardNormal;
+
+/// Polynomial mutation - the usual companion of [`SimulatedBinaryCrossover`]:
+/// nudges genes by amounts drawn from a polynomial distribution, small
+/// changes being much more likely than large ones.
+///
+/// Genes with [`GeneBounds`] (see [`Chromosome::with_bounds()`]) never
+/// leave them and can move at most as far as their bounds are; others move
+/// by at most 1.0.
+#[derive(Clone, Debug)]
+pub struct PolynomialMutation {
+    /// Probability of changing a gene:
+    /// - 0.0 = no genes will be touched
+    /// - 1.0 = all genes will be touched
+    chance: f32,
+
+    /// Distribution index - how small the changes tend to be:
+    /// - 0.0 = all magnitudes are equally likely
+    /// - 20.0 = the usual choice; mostly tiny changes
+    eta: f32,
+}
+
+impl PolynomialMutation {
+    pub fn new(chance: f32, eta: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(eta >= 0.0);
+
+        Self { chance, eta }
+    }
+
+    /// Change of a gene, relative to its range, for a random `u`; `below`
+    /// and `above` are how far (relative to the range) the gene is from its
+    /// bounds.
+    fn delta(&self, u: f32, below: f32, above: f32) -> f32 {
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        if u < 0.5 {
+            let val = 2.0 * u + (1.0 - 2.0 * u) * (1.0 - below).powf(self.eta + 1.0);
+
+            val.powf(exponent) - 1.0
+        } else {
+            let val = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * (1.0 - above).powf(self.eta + 1.0);
+
+            1.0 - val.powf(exponent)
+        }
+    }
+}
+
+impl MutationMethod for PolynomialMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let bounds = child.bounds().cloned();
+
+        for (idx, gene) in child.iter_mut().enumerate() {
+            if !rng.gen_bool(self.chance as f64) {
+                continue;
+            }
+
+            let u = rng.gen::<f32>();
+
+            match bounds.as_ref().and_then(|bounds| bounds.get(idx)) {
+                Some((min, max)) => {
+                    // Genes can end up out of bounds thanks to other
+                    // methods, which would leave the distribution undefined
+                    *gene = gene.clamp(min, max);
+
+                    let range = max - min;
+
+                    let below = (*gene - min) / range;
+                    let above = (max - *gene) / range;
+
+                    *gene += self.delta(u, below, above) * range;
+                    *gene = gene.clamp(min, max);
+                }
+
+                // Bounds at least a whole range away don't restrict the
+                // change at all
+                None => *gene += self.delta(u, 1.0, 1.0),
+            }
+        }
+    }
+}
+
+/// Nudges random genes by amounts drawn from the normal distribution with
+/// given standard deviation - small changes are common, large ones rare but
+/// possible.
+#[derive(Clone, Debug)]
+pub struct NormalMutation {
+    /// Probability of changing a gene:
+    /// - 0.0 = no genes will be touched
+    /// - 1.0 = all genes will be touched
+    chance: f32,
+
+    /// Standard deviation of the change - about two thirds of touched genes
+    /// change by less than that
+    sigma: f32,
+}
+
+impl NormalMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(sigma >= 0.0);
+
+        Self { chance, sigma }
+    }
+}
+
+impl MutationMethod for NormalMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        mutate_normally(rng, child, self.chance, self.sigma);
+    }
+}
+
+fn mutate_normally(rng: &mut dyn RngCore, child: &mut Chromosome, chance: f32, sigma: f32) {
+    for gene in child.iter_mut() {
+        if rng.gen_bool(chance as f64) {
+            *gene += sigma * rng.sample::<f32, _>(StandardNormal);
+        }
+    }
+}
+
+/// Value changing with generations, e.g. a mutation rate that starts high
+/// to explore and decays to fine-tune.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Schedule {
+    Constant {
+        value: f32,
+    },
+
+    /// Goes from `start` to `end` over `generations`, then stays at `end`
+    Linear {
+        start: f32,
+        end: f32,
+        generations: usize,
+    },
+
+    /// Gets multiplied by `decay` every generation, but never drops below
+    /// `min`
+    Exponential {
+        start: f32,
+        decay: f32,
+
+        #[serde(default)]
+        min: f32,
+    },
+}
+
+impl Schedule {
+    pub fn value(&self, generation: usize) -> f32 {
+        match *self {
+            Self::Constant { value } => value,
+
+            Self::Linear {
+                start,
+                end,
+                generations,
+            } => {
+                if generation >= generations {
+                    end
+                } else {
+                    start + (end - start) * generation as f32 / generations as f32
+                }
+            }
+
+            Self::Exponential { start, decay, min } => {
+                (start * decay.powi(generation.min(i32::MAX as usize) as i32)).max(min)
+            }
+        }
+    }
+}
+
+/// [`NormalMutation`] with chance and standard deviation following
+/// [`Schedule`]s, advanced by [`GeneticAlgorithm::evolve()`].
+///
+/// Chance gets clamped to `0.0..=1.0`, standard deviation to `0.0..`; a
+/// schedule yielding NaN counts as `0.0`.
+#[derive(Clone, Debug)]
+pub struct ScheduledMutation {
+    chance: Schedule,
+    sigma: Schedule,
+    generation: usize,
+}
+
+impl ScheduledMutation {
+    pub fn new(chance: Schedule, sigma: Schedule) -> Self {
+        Self {
+            chance,
+            sigma,
+            generation: 0,
+        }
+    }
+
+    pub fn chance(&self) -> f32 {
+        let chance = self.chance.value(self.generation);
+
+        if chance.is_nan() {
+            0.0
+        } else {
+            chance.clamp(0.0, 1.0)
+        }
+    }
+
+    pub fn sigma(&self) -> f32 {
+        // `max()` ignores NaN
+        self.sigma.value(self.generation).max(0.0)
+    }
+}
+
+impl MutationMethod for ScheduledMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        mutate_normally(rng, child, self.chance(), self.sigma());
+    }
+
+    fn adapt(&mut self, feedback: &MutationFeedback) {
+        self.generation = feedback.generation;
+    }
+}
+
+/// [`NormalMutation`] with standard deviation following Rechenberg's 1/5th
+/// success rule: if more than a fifth of children turn out fitter than
+/// their parents, the search is too timid and the steps grow; if fewer,
+/// they shrink.
+#[derive(Clone, Debug)]
+pub struct OneFifthRuleMutation {
+    chance: f32,
+    sigma: f32,
+
+    /// How much the standard deviation changes each generation:
+    /// `sigma / factor` when growing, `sigma * factor` when shrinking
+    factor: f32,
+}
+
+impl OneFifthRuleMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(sigma > 0.0);
+
+        Self {
+            chance,
+            sigma,
+            factor: 0.817,
+        }
+    }
+
+    /// Changes how quickly the standard deviation adapts - 0.817 by
+    /// default; closer to 1.0 is slower.
+    pub fn with_factor(mut self, factor: f32) -> Self {
+        assert!(factor > 0.0 && factor < 1.0);
+
+        self.factor = factor;
+        self
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+}
+
+impl MutationMethod for OneFifthRuleMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        mutate_normally(rng, child, self.chance, self.sigma);
+    }
+
+    fn adapt(&mut self, feedback: &MutationFeedback) {
+        match feedback.success_rate {
+            Some(rate) if rate > 0.2 => self.sigma /= self.factor,
+            Some(rate) if rate < 0.2 => self.sigma *= self.factor,
+            _ => (),
+        }
+    }
+}
+
+/// Self-adaptive mutation, as in evolution strategies: each chromosome
+/// carries its own step size per gene (see [`Chromosome::step_sizes()`]),
+/// which gets mutated log-normally before being used to mutate the gene.
+///
+/// Step sizes that produce fit children survive along with them, so they
+/// adapt to the problem without any outside feedback. Crossover averages
+/// parents' step sizes; chromosomes without any start with `initial_step`.
+///
+/// Individuals have to hand back the very chromosomes they were created
+/// with - ones rebuilt from e.g. a neural network lose their step sizes.
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveMutation {
+    initial_step: f32,
+    min_step: f32,
+}
+
+impl SelfAdaptiveMutation {
+    pub fn new(initial_step: f32) -> Self {
+        assert!(initial_step > 0.0);
+
+        Self {
+            initial_step,
+            min_step: 1e-6,
+        }
+    }
+
+    /// Keeps step sizes from collapsing below given value (1e-6 by
+    /// default), which would stop the evolution.
+    pub fn with_min_step(mut self, min_step: f32) -> Self {
+        assert!(min_step >= 0.0);
+
+        self.min_step = min_step;
+        self
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let n = child.len() as f32;
+
+        if child.step_sizes().len() != child.len() {
+            *child.step_sizes_mut() = vec![self.initial_step; child.len()];
+        }
+
+        // Learning rates recommended by Schwefel
+        let tau_global = 1.0 / (2.0 * n).sqrt();
+        let tau_local = 1.0 / (2.0 * n.sqrt()).sqrt();
+
+        let global = tau_global * rng.sample::<f32, _>(StandardNormal);
+        let mut step_sizes = std::mem::take(child.step_sizes_mut());
+
+        for (gene, step) in child.iter_mut().zip(&mut step_sizes) {
+            let local = tau_local * rng.sample::<f32, _>(StandardNormal);
+
+            *step = (*step * (global + local).exp()).max(self.min_step);
+            *gene += *step * rng.sample::<f32, _>(StandardNormal);
+        }
+
+        *child.step_sizes_mut() = step_sizes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    mod polynomial_mutation {
+        use super::*;
+
+        fn actual(method: PolynomialMutation) -> Vec<f32> {
+            actual_within(method, None)
+        }
+
+        fn actual_within(method: PolynomialMutation, bounds: Option<GeneBounds>) -> Vec<f32> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child: Chromosome = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+
+            if let Some(bounds) = bounds {
+                child = child.with_bounds(bounds);
+            }
+
+            method.mutate(&mut rng, &mut child);
+
+            child.into_iter().collect()
+        }
+
+        #[test]
+        fn with_zero_chance() {
+            let actual = actual(PolynomialMutation::new(0.0, 20.0));
+
+            assert_relative_eq!(actual.as_slice(), [1.0, 2.0, 3.0, 4.0, 5.0].as_ref());
+        }
+
+        #[test]
+        fn with_max_chance() {
+            let actual = actual(PolynomialMutation::new(1.0, 20.0));
+            let expected = [0.9543049, 2.0519526, 3.0779574, 4.014417, 5.0347204];
+
+            assert_relative_eq!(actual.as_slice(), expected.as_ref());
+        }
+
+        #[test]
+        fn with_bounds() {
+            let bounds =
+                GeneBounds::per_gene([(0.0, 1.0), (1.5, 2.5), (2.9, 3.0), (0.0, 10.0), (5.0, 6.0)]);
+
+            let actual = actual_within(PolynomialMutation::new(1.0, 5.0), Some(bounds.clone()));
+            let expected = [0.84899473, 2.1659255, 3.0, 4.4932575, 5.1163383];
+
+            assert_relative_eq!(actual.as_slice(), expected.as_ref());
+
+            for (idx, gene) in actual.into_iter().enumerate() {
+                let (min, max) = bounds.get(idx).unwrap();
+
+                assert!((min..=max).contains(&gene), "gene {idx} = {gene}");
+            }
+        }
+
+        #[test]
+        fn with_genes_out_of_bounds() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = PolynomialMutation::new(1.0, 20.0);
+
+            for _ in 0..1000 {
+                let mut child =
+                    Chromosome::from_iter([2.0, -3.0]).with_bounds(GeneBounds::uniform(0.0, 1.0));
+
+                method.mutate(&mut rng, &mut child);
+
+                assert!(child.iter().all(|gene| (0.0..=1.0).contains(gene)));
+            }
+        }
+
+        #[test]
+        fn prefers_small_changes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = PolynomialMutation::new(1.0, 20.0);
+
+            let mut child: Chromosome = vec![0.0; 1000].into_iter().collect();
+
+            method.mutate(&mut rng, &mut child);
+
+            let small = child.iter().filter(|gene| gene.abs() < 0.1).count();
+
+            assert!(child.iter().all(|gene| gene.abs() <= 1.0));
+
+            // Expected: 1 - 0.9^21 = ~89%
+            assert_eq!(small, 893);
+        }
+    }
+
+    /// Mean and standard deviation of how much the genes of a zeroed
+    /// chromosome moved.
+    fn spread(genes: &Chromosome) -> (f32, f32) {
+        let n = genes.len() as f32;
+        let mean = genes.iter().sum::<f32>() / n;
+        let variance = genes.iter().map(|g| (g - mean).powi(2)).sum::<f32>() / n;
+
+        (mean, variance.sqrt())
+    }
+
+    fn zeros(len: usize) -> Chromosome {
+        vec![0.0; len].into_iter().collect()
+    }
+
+    mod normal_mutation {
+        use super::*;
+
+        #[test]
+        fn mutate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child: Chromosome = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+
+            NormalMutation::new(0.5, 1.0).mutate(&mut rng, &mut child);
+
+            let actual: Vec<_> = child.into_iter().collect();
+            let expected = [1.0, 2.0, 1.064953, 2.935384, 3.997438];
+
+            assert_relative_eq!(actual.as_slice(), expected.as_ref());
+        }
+
+        #[test]
+        fn is_normally_distributed() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = zeros(10000);
+
+            NormalMutation::new(1.0, 0.5).mutate(&mut rng, &mut child);
+
+            let (mean, sigma) = spread(&child);
+
+            assert_relative_eq!(mean, 0.0, epsilon = 0.01);
+            assert_relative_eq!(sigma, 0.5, epsilon = 0.01);
+
+            // About 95% within two standard deviations
+            let within = child.iter().filter(|g| g.abs() < 1.0).count();
+
+            assert_eq!(within, 9537);
+        }
+    }
+
+    mod schedule {
+        use super::*;
+
+        #[test]
+        fn linear() {
+            let schedule = Schedule::Linear {
+                start: 0.5,
+                end: 0.1,
+                generations: 4,
+            };
+
+            let actual: Vec<_> = (0..6).map(|gen| schedule.value(gen)).collect();
+
+            assert_relative_eq!(actual.as_slice(), [0.5, 0.4, 0.3, 0.2, 0.1, 0.1].as_ref());
+        }
+
+        #[test]
+        fn exponential() {
+            let schedule = Schedule::Exponential {
+                start: 1.0,
+                decay: 0.5,
+                min: 0.2,
+            };
+
+            let actual: Vec<_> = (0..5).map(|gen| schedule.value(gen)).collect();
+
+            assert_relative_eq!(actual.as_slice(), [1.0, 0.5, 0.25, 0.2, 0.2].as_ref());
+        }
+
+        #[test]
+        fn scheduled_mutation() {
+            let mut method = ScheduledMutation::new(
+                Schedule::Constant { value: 2.0 },
+                Schedule::Exponential {
+                    start: 0.4,
+                    decay: 0.5,
+                    min: 0.0,
+                },
+            );
+
+            assert_eq!((method.chance(), method.sigma()), (1.0, 0.4));
+
+            method.adapt(&MutationFeedback {
+                generation: 3,
+                success_rate: None,
+            });
+
+            assert_eq!((method.chance(), method.sigma()), (1.0, 0.05));
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = zeros(10000);
+
+            method.mutate(&mut rng, &mut child);
+
+            assert_relative_eq!(spread(&child).1, 0.05, epsilon = 0.001);
+        }
+
+        #[test]
+        fn scheduled_mutation_with_nan() {
+            let method = ScheduledMutation::new(
+                Schedule::Constant { value: f32::NAN },
+                Schedule::Constant { value: f32::NAN },
+            );
+
+            assert_eq!((method.chance(), method.sigma()), (0.0, 0.0));
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = zeros(100);
+
+            method.mutate(&mut rng, &mut child);
+
+            assert_eq!(child, zeros(100));
+        }
+    }
+
+    mod one_fifth_rule_mutation {
+        use super::*;
+
+        fn adapted(success_rate: Option<f32>) -> f32 {
+            let mut method = OneFifthRuleMutation::new(1.0, 1.0).with_factor(0.5);
+
+            method.adapt(&MutationFeedback {
+                generation: 1,
+                success_rate,
+            });
+
+            method.sigma()
+        }
+
+        #[test]
+        fn adapt() {
+            assert_eq!(adapted(Some(0.5)), 2.0);
+            assert_eq!(adapted(Some(0.2)), 1.0);
+            assert_eq!(adapted(Some(0.1)), 0.5);
+            assert_eq!(adapted(None), 1.0);
+        }
+    }
+
+    mod self_adaptive_mutation {
+        use super::*;
+
+        #[test]
+        fn mutate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child: Chromosome = vec![1.0, 2.0, 3.0].into_iter().collect();
+
+            SelfAdaptiveMutation::new(0.1).mutate(&mut rng, &mut child);
+
+            let genes: Vec<_> = child.iter().copied().collect();
+
+            assert_relative_eq!(genes.as_slice(), [0.73897743, 1.9583164, 2.787147].as_ref());
+            assert_relative_eq!(
+                child.step_sizes(),
+                [0.21819726, 0.062050033, 0.09904859].as_ref()
+            );
+        }
+
+        #[test]
+        fn uses_step_sizes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut child =
+                zeros(10000).with_step_sizes((0..10000).map(|n| if n < 5000 { 0.01 } else { 1.0 }));
+
+            SelfAdaptiveMutation::new(0.1).mutate(&mut rng, &mut child);
+
+            let genes: Vec<_> = child.iter().copied().collect();
+            let small = spread(&genes[..5000].iter().copied().collect()).1;
+            let large = spread(&genes[5000..].iter().copied().collect()).1;
+
+            assert!(small < 0.02, "{small}");
+            assert!(large > 0.5, "{large}");
+        }
+
+        #[test]
+        fn with_min_step() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = zeros(100).with_step_sizes(vec![1e-9; 100]);
+
+            SelfAdaptiveMutation::new(0.1)
+                .with_min_step(0.001)
+                .mutate(&mut rng, &mut child);
+
+            assert!(child.step_sizes().iter().all(|&step| step >= 0.001));
+        }
+    }
+}
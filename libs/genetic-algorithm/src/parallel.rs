@@ -0,0 +1,115 @@
+use crate::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+impl<S> GeneticAlgorithm<S>
+where
+    S: SelectionMethod + Sync,
+{
+    /// Same as [`Self::evolve()`], but breeds children on rayon's thread
+    /// pool.
+    ///
+    /// Fitness isn't evaluated here - the algorithm only reads what
+    /// [`Individual::fitness()`] returns - so evaluating the population in
+    /// parallel is up to the caller (e.g. with rayon's `par_iter_mut()`
+    /// before calling this method).
+    ///
+    /// Each child gets its own random number generator: a stream of a
+    /// ChaCha seeded from `rng` once per generation - so the outcome
+    /// depends only on `rng`, not on the number of threads or the order in
+    /// which they happen to run. It's different from the outcome of
+    /// [`Self::evolve()`] for the same `rng`, though.
+    pub fn par_evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual + Send + Sync,
+    {
+        let (mut new_population, parents, stats) = self.prepare(rng, population);
+        let seed = rng.gen();
+        let this = &*self;
+
+        new_population.par_extend(
+            parents
+                .par_chunks_exact(2)
+                .enumerate()
+                .map(|(idx, parents)| {
+                    let mut rng = ChaCha8Rng::from_seed(seed);
+
+                    rng.set_stream(idx as u64);
+                    this.breed(&mut rng, parents[0], parents[1])
+                }),
+        );
+
+        (new_population, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rayon::ThreadPoolBuilder;
+
+    fn population() -> Vec<TestIndividual> {
+        (0..50)
+            .map(|n| TestIndividual::create((0..10).map(|m| ((n * m) % 7) as f32).collect()))
+            .collect()
+    }
+
+    /// Chromosomes of the population after a few generations, evolved on
+    /// given number of threads.
+    fn evolve(threads: usize) -> Vec<Vec<f32>> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+
+        pool.install(|| {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let mut ga = GeneticAlgorithm::builder()
+                .selection(TournamentSelection::new(2))
+                .mutation(NormalMutation::new(0.2, 0.5))
+                .elitism(2)
+                .build();
+
+            let mut population = population();
+
+            for _ in 0..10 {
+                population = ga.par_evolve(&mut rng, &population).0;
+            }
+
+            population
+                .iter()
+                .map(|individual| individual.chromosome().iter().copied().collect())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn is_independent_of_thread_count() {
+        let expected = evolve(1);
+
+        assert_eq!(expected.len(), 50);
+
+        for threads in [2, 3, 8] {
+            assert_eq!(evolve(threads), expected, "{threads} threads");
+        }
+    }
+
+    #[test]
+    fn evolves() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::builder().elitism(1).build();
+
+        let population = population();
+        let before = Statistics::new(&population);
+
+        let (population, _) = ga.par_evolve(&mut rng, &population);
+        let after = Statistics::new(&population);
+
+        assert_eq!(population.len(), 50);
+        assert!(after.max_fitness >= before.max_fitness);
+        assert!(after.avg_fitness > before.avg_fitness);
+    }
+}
@@ -0,0 +1,489 @@
+use crate::*;
+
+/// Picks `size` random individuals (possibly repeating some) and lets the
+/// fittest one win.
+///
+/// Unlike [`RouletteWheelSelection`], only the order of fitnesses matters
+/// - so it works with zero or negative fitness, and a single outlier
+/// doesn't dominate the population. Larger tournaments favour fitter
+/// individuals more strongly; a tournament of one selects uniformly.
+#[derive(Clone, Debug)]
+pub struct TournamentSelection {
+    size: usize,
+
+    /// Probability of the fittest contestant winning; if it doesn't, the
+    /// second one wins with the same probability and so on, with the last
+    /// contestant winning whatever's left:
+    /// - 1.0 = fittest contestant always wins
+    /// - 0.0 = last contestant always wins
+    chance: f32,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "tournament must have at least one contestant");
+
+        Self { size, chance: 1.0 }
+    }
+
+    /// Lets the fittest contestant win only with given probability;
+    /// otherwise the second one gets the same chance and so on.
+    pub fn with_chance(mut self, chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+
+        self.chance = chance;
+        self
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got empty population");
+
+        let mut contestants: Vec<_> = (0..self.size)
+            .map(|_| &population[rng.gen_range(0..population.len())])
+            .collect();
+
+        if self.chance >= 1.0 {
+            return contestants
+                .into_iter()
+                .max_by(|a, b| a.fitness().total_cmp(&b.fitness()))
+                .unwrap();
+        }
+
+        contestants.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+        let last = contestants.len() - 1;
+
+        contestants
+            .iter()
+            .take(last)
+            .find(|_| rng.gen_bool(self.chance as f64))
+            .unwrap_or(&contestants[last])
+    }
+}
+
+/// Selects individuals with probability depending only on their rank in
+/// the population (sorted by fitness), not on the fitness itself - so a
+/// single outlier gets only slightly more offspring than the runner-up.
+#[derive(Clone, Debug)]
+pub struct RankSelection {
+    ranking: Ranking,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Ranking {
+    Linear { pressure: f32 },
+    Exponential { base: f32 },
+}
+
+impl RankSelection {
+    /// Probability grows linearly with rank; the best individual gets
+    /// `pressure` times the average probability, the worst one
+    /// `2 - pressure` times:
+    /// - 1.0 = everybody's equally likely
+    /// - 2.0 = the worst individual is never selected
+    pub fn linear(pressure: f32) -> Self {
+        assert!((1.0..=2.0).contains(&pressure));
+
+        Self {
+            ranking: Ranking::Linear { pressure },
+        }
+    }
+
+    /// Probability grows exponentially with rank - each individual is
+    /// `base` times as likely to be selected as the next better one:
+    /// - 1.0 = everybody's equally likely
+    /// - close to 0.0 = almost always the best individual
+    pub fn exponential(base: f32) -> Self {
+        assert!(base > 0.0 && base <= 1.0);
+
+        Self {
+            ranking: Ranking::Exponential { base },
+        }
+    }
+
+    /// Selection weight of each individual, in the population's order.
+    fn weights<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        let n = population.len();
+        let mut order: Vec<_> = (0..n).collect();
+
+        order.sort_by(|&a, &b| population[a].fitness().total_cmp(&population[b].fitness()));
+
+        let mut weights = vec![0.0; n];
+
+        // Rank 0 is the worst individual, `n - 1` the best one
+        for (rank, idx) in order.into_iter().enumerate() {
+            weights[idx] = match self.ranking {
+                Ranking::Linear { pressure } if n > 1 => {
+                    (2.0 - pressure) + 2.0 * (pressure - 1.0) * rank as f32 / (n - 1) as f32
+                }
+                Ranking::Linear { .. } => 1.0,
+                Ranking::Exponential { base } => base.powi((n - 1 - rank) as i32),
+            };
+        }
+
+        weights
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got empty population");
+
+        let weights =
+            WeightedIndex::new(self.weights(population)).expect("rank weights are always valid");
+
+        (0..count)
+            .map(|_| &population[weights.sample(rng)])
+            .collect()
+    }
+}
+
+/// Fitness-proportional selection, like [`RouletteWheelSelection`], but
+/// selecting many individuals with a single spin of a wheel with equally
+/// spaced pointers.
+///
+/// Each individual gets selected either `floor(expected)` or
+/// `ceil(expected)` times, where `expected` is proportional to its fitness
+/// - roulette, spun separately for each selection, can by chance pick the
+/// same individual over and over. Selected individuals are returned
+/// shuffled.
+///
+/// Fitness must not be negative; if everybody's fitness is zero, selects
+/// uniformly.
+#[derive(Clone, Debug)]
+pub struct StochasticUniversalSampling;
+
+impl SelectionMethod for StochasticUniversalSampling {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got empty population");
+
+        let fitnesses: Vec<_> = population.iter().map(|i| i.fitness()).collect();
+
+        assert!(
+            fitnesses.iter().all(|&f| f >= 0.0),
+            "fitness must not be negative"
+        );
+
+        let total: f32 = fitnesses.iter().sum();
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        // Nobody's better than anybody else
+        if total <= 0.0 {
+            return (0..count)
+                .map(|_| population.choose(rng).unwrap())
+                .collect();
+        }
+
+        let step = total / count as f32;
+        let start = rng.gen_range(0.0..step);
+
+        let mut selected = Vec::with_capacity(count);
+        let mut idx = 0;
+        let mut wheel = fitnesses[0];
+
+        for pointer in (0..count).map(|n| start + n as f32 * step) {
+            // Last individual catches pointers pushed past the end by
+            // rounding errors
+            while wheel <= pointer && idx < population.len() - 1 {
+                idx += 1;
+                wheel += fitnesses[idx];
+            }
+
+            selected.push(&population[idx]);
+        }
+
+        selected.shuffle(rng);
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeMap;
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ]
+    }
+
+    fn histogram(
+        method: impl SelectionMethod,
+        population: &[TestIndividual],
+    ) -> BTreeMap<i32, i32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut histogram = BTreeMap::new();
+
+        for _ in 0..1000 {
+            let fitness = method.select(&mut rng, population).fitness() as i32;
+
+            *histogram.entry(fitness).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    mod tournament_selection {
+        use super::*;
+
+        #[test]
+        fn of_one() {
+            let actual = histogram(TournamentSelection::new(1), &population());
+
+            // Every individual is (roughly) equally likely
+            let expected = BTreeMap::from_iter([(1, 265), (2, 243), (3, 231), (4, 261)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn of_two() {
+            let actual = histogram(TournamentSelection::new(2), &population());
+
+            // Expected: 1/16, 3/16, 5/16 and 7/16
+            let expected = BTreeMap::from_iter([(1, 75), (2, 177), (3, 300), (4, 448)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn of_four() {
+            let actual = histogram(TournamentSelection::new(4), &population());
+
+            // Expected: 1/256, 15/256, 65/256 and 175/256
+            let expected = BTreeMap::from_iter([(1, 6), (2, 61), (3, 251), (4, 682)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn with_chance() {
+            let actual = histogram(TournamentSelection::new(2).with_chance(0.75), &population());
+
+            // Fittest contestant wins 3/4 of the time, so compared to
+            // `of_two()` weaker individuals get selected more often
+            let expected = BTreeMap::from_iter([(1, 166), (2, 209), (3, 267), (4, 358)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn with_zero_chance() {
+            let actual = histogram(TournamentSelection::new(4).with_chance(0.0), &population());
+
+            // Mirror image of `of_four()`
+            let expected = BTreeMap::from_iter([(1, 672), (2, 263), (3, 63), (4, 2)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn with_zero_fitness() {
+            let population = vec![TestIndividual::new(0.0); 4];
+            let actual = histogram(TournamentSelection::new(3), &population);
+
+            assert_eq!(actual, BTreeMap::from_iter([(0, 1000)]));
+        }
+
+        #[test]
+        #[should_panic(expected = "at least one contestant")]
+        fn of_zero() {
+            TournamentSelection::new(0);
+        }
+    }
+
+    mod rank_selection {
+        use super::*;
+
+        #[test]
+        fn linear() {
+            let actual = histogram(RankSelection::linear(2.0), &population());
+
+            // Expected: 0, 1/6, 1/3 and 1/2
+            let expected = BTreeMap::from_iter([(2, 164), (3, 318), (4, 518)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn linear_without_pressure() {
+            let actual = histogram(RankSelection::linear(1.0), &population());
+
+            // Every individual is (roughly) equally likely
+            let expected = BTreeMap::from_iter([(1, 253), (2, 248), (3, 229), (4, 270)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn exponential() {
+            let actual = histogram(RankSelection::exponential(0.5), &population());
+
+            // Expected: 1/15, 2/15, 4/15 and 8/15
+            let expected = BTreeMap::from_iter([(1, 60), (2, 142), (3, 247), (4, 551)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn ignores_outliers() {
+            let mut population = population();
+
+            population[0] = TestIndividual::new(1000.0);
+
+            let actual = histogram(RankSelection::linear(2.0), &population);
+
+            // Outlier gets just what the best individual got in `linear()`
+            let expected = BTreeMap::from_iter([(3, 152), (4, 347), (1000, 501)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn of_one() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let population = [TestIndividual::new(1.0)];
+
+            for method in [RankSelection::linear(2.0), RankSelection::exponential(0.5)] {
+                assert_eq!(method.select(&mut rng, &population).fitness(), 1.0);
+            }
+        }
+    }
+
+    mod stochastic_universal_sampling {
+        use super::*;
+
+        fn histogram(population: &[TestIndividual], count: usize) -> BTreeMap<i32, i32> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut histogram = BTreeMap::new();
+
+            for individual in StochasticUniversalSampling.select_many(&mut rng, population, count) {
+                *histogram.entry(individual.fitness() as i32).or_insert(0) += 1;
+            }
+
+            histogram
+        }
+
+        #[test]
+        fn select_many() {
+            // Single spin yields exactly the expected counts
+            let actual = histogram(&population(), 1000);
+            let expected = BTreeMap::from_iter([(1, 100), (2, 200), (3, 300), (4, 400)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn select_many_with_uneven_shares() {
+            // Expected: 0.7, 1.4, 2.1 and 2.8 - each gets rounded one way or
+            // the other
+            let actual = histogram(&population(), 7);
+            let expected = BTreeMap::from_iter([(2, 2), (3, 2), (4, 3)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn select_many_is_shuffled() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let population = population();
+
+            let actual: Vec<_> = StochasticUniversalSampling
+                .select_many(&mut rng, &population, 8)
+                .into_iter()
+                .map(|individual| individual.fitness() as i32)
+                .collect();
+
+            assert_eq!(actual, [4, 4, 2, 1, 2, 3, 4, 3]);
+        }
+
+        #[test]
+        fn select() {
+            let actual = super::histogram(StochasticUniversalSampling, &population());
+
+            // Single selection is the same as spinning a roulette wheel
+            let expected = BTreeMap::from_iter([(1, 98), (2, 202), (3, 278), (4, 422)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn with_zero_fitness() {
+            let mut population = population();
+
+            population[1] = TestIndividual::new(0.0);
+
+            let actual = histogram(&population, 900);
+            let expected = BTreeMap::from_iter([(2, 200), (3, 300), (4, 400)]);
+
+            assert_eq!(actual, expected);
+
+            let population = vec![TestIndividual::new(0.0); 4];
+
+            assert_eq!(histogram(&population, 10), BTreeMap::from_iter([(0, 10)]));
+        }
+    }
+
+    #[test]
+    fn select_many() {
+        let population = population();
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let actual = RouletteWheelSelection.select_many(&mut rng, &population, 10);
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let expected: Vec<_> = (0..10)
+            .map(|_| RouletteWheelSelection.select(&mut rng, &population))
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+}
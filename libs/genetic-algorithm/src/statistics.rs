@@ -0,0 +1,287 @@
+use crate::*;
+
+/// Summary of a population's fitness and genetic diversity.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+    pub median_fitness: f32,
+
+    /// Standard deviation of the population's fitness
+    pub std_dev_fitness: f32,
+
+    /// Fitness above which lie three quarters of the population
+    pub lower_quartile_fitness: f32,
+
+    /// Fitness above which lies a quarter of the population
+    pub upper_quartile_fitness: f32,
+
+    /// Index of the fittest individual in the population (the first one,
+    /// if there's a tie)
+    pub best_index: usize,
+
+    /// Standard deviation of each gene across the population, averaged
+    /// over all genes:
+    /// - 0.0 = everybody has the same genes
+    /// - larger = genes are more spread out
+    pub diversity: f32,
+}
+
+impl Statistics {
+    pub fn new<I>(population: &[I]) -> Self
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let fitnesses: Vec<_> = population.iter().map(|i| i.fitness()).collect();
+        let len = fitnesses.len() as f32;
+
+        let mut min_fitness = fitnesses[0];
+        let mut max_fitness = min_fitness;
+        let mut best_index = 0;
+        let mut sum_fitness = 0.0;
+
+        for (idx, &fitness) in fitnesses.iter().enumerate() {
+            if fitness > max_fitness {
+                max_fitness = fitness;
+                best_index = idx;
+            }
+
+            min_fitness = min_fitness.min(fitness);
+            sum_fitness += fitness;
+        }
+
+        let avg_fitness = sum_fitness / len;
+
+        let variance = fitnesses
+            .iter()
+            .map(|fitness| (fitness - avg_fitness).powi(2))
+            .sum::<f32>()
+            / len;
+
+        let mut sorted = fitnesses;
+
+        sorted.sort_by(f32::total_cmp);
+
+        Self {
+            min_fitness,
+            max_fitness,
+            avg_fitness,
+            median_fitness: percentile(&sorted, 0.5),
+            std_dev_fitness: variance.sqrt(),
+            lower_quartile_fitness: percentile(&sorted, 0.25),
+            upper_quartile_fitness: percentile(&sorted, 0.75),
+            best_index,
+            diversity: diversity(population),
+        }
+    }
+}
+
+/// Value below which lies given fraction of `sorted`, interpolated linearly
+/// between its elements.
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    let pos = fraction * (sorted.len() - 1) as f32;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f32)
+}
+
+/// See [`Statistics::diversity`]; if chromosomes differ in length, each
+/// gene counts only the chromosomes that have it.
+fn diversity<I>(population: &[I]) -> f32
+where
+    I: Individual,
+{
+    // Per gene: how many chromosomes have it, their mean and sum of squared
+    // differences from the mean - updated with Welford's algorithm, since
+    // subtracting squared mean from mean of squares cancels catastrophically
+    // for genes far from zero
+    let mut genes: Vec<(f32, f32, f32)> = Vec::new();
+
+    for individual in population {
+        let chromosome = individual.chromosome();
+
+        if genes.len() < chromosome.len() {
+            genes.resize(chromosome.len(), (0.0, 0.0, 0.0));
+        }
+
+        for (&gene, (count, mean, m2)) in chromosome.iter().zip(&mut genes) {
+            *count += 1.0;
+
+            let delta = gene - *mean;
+
+            *mean += delta / *count;
+            *m2 += delta * (gene - *mean);
+        }
+    }
+
+    if genes.is_empty() {
+        return 0.0;
+    }
+
+    let sum_std_dev: f32 = genes
+        .iter()
+        .map(|&(count, _, m2)| (m2 / count).sqrt())
+        .sum();
+
+    sum_std_dev / genes.len() as f32
+}
+
+/// [`Statistics`] of consecutive generations, e.g. for plotting how the
+/// evolution went.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    generations: Vec<Statistics>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, stats: Statistics) {
+        self.generations.push(stats);
+    }
+
+    /// Recorded statistics, oldest first.
+    pub fn generations(&self) -> &[Statistics] {
+        &self.generations
+    }
+
+    pub fn last(&self) -> Option<&Statistics> {
+        self.generations.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.generations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generations.is_empty()
+    }
+
+    /// Highest fitness of all of the recorded generations.
+    pub fn best_fitness(&self) -> Option<f32> {
+        self.generations
+            .iter()
+            .map(|stats| stats.max_fitness)
+            .reduce(f32::max)
+    }
+
+    /// One of the statistics of each generation, e.g.
+    /// `history.series(|stats| stats.median_fitness)`.
+    pub fn series(&self, f: impl Fn(&Statistics) -> f32) -> Vec<f32> {
+        self.generations.iter().map(f).collect()
+    }
+}
+
+impl Extend<Statistics> for History {
+    fn extend<T: IntoIterator<Item = Statistics>>(&mut self, iter: T) {
+        self.generations.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use approx::assert_relative_eq;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().copied().collect())
+    }
+
+    #[test]
+    fn fitness() {
+        // Fitness: 3, 1, 7, 4, 5
+        let population = [
+            individual(&[1.0, 2.0]),
+            individual(&[1.0, 0.0]),
+            individual(&[4.0, 3.0]),
+            individual(&[2.0, 2.0]),
+            individual(&[3.0, 2.0]),
+        ];
+
+        let stats = Statistics::new(&population);
+
+        assert_eq!(stats.min_fitness, 1.0);
+        assert_eq!(stats.max_fitness, 7.0);
+        assert_eq!(stats.avg_fitness, 4.0);
+        assert_eq!(stats.median_fitness, 4.0);
+        assert_eq!(stats.lower_quartile_fitness, 3.0);
+        assert_eq!(stats.upper_quartile_fitness, 5.0);
+        assert_eq!(stats.best_index, 2);
+
+        // sqrt((1 + 9 + 9 + 0 + 1) / 5)
+        assert_relative_eq!(stats.std_dev_fitness, 2.0);
+    }
+
+    #[test]
+    fn interpolated_percentiles() {
+        let population = [
+            individual(&[4.0]),
+            individual(&[1.0]),
+            individual(&[3.0]),
+            individual(&[2.0]),
+        ];
+
+        let stats = Statistics::new(&population);
+
+        assert_eq!(stats.median_fitness, 2.5);
+        assert_eq!(stats.lower_quartile_fitness, 1.75);
+        assert_eq!(stats.upper_quartile_fitness, 3.25);
+    }
+
+    #[test]
+    fn single_individual() {
+        let stats = Statistics::new(&[individual(&[1.0, 2.0])]);
+
+        assert_eq!(stats.median_fitness, 3.0);
+        assert_eq!(stats.lower_quartile_fitness, 3.0);
+        assert_eq!(stats.std_dev_fitness, 0.0);
+        assert_eq!(stats.best_index, 0);
+        assert_eq!(stats.diversity, 0.0);
+    }
+
+    #[test]
+    fn diversity() {
+        let population = [individual(&[1.0, 5.0]), individual(&[1.0, 5.0])];
+        assert_eq!(Statistics::new(&population).diversity, 0.0);
+
+        // Std devs of genes: 1 and 3
+        let population = [individual(&[0.0, 5.0]), individual(&[2.0, -1.0])];
+        assert_relative_eq!(Statistics::new(&population).diversity, 2.0);
+
+        // Last gene only in one chromosome
+        let population = [individual(&[0.0]), individual(&[2.0, 7.0])];
+        assert_relative_eq!(Statistics::new(&population).diversity, 0.5);
+
+        // Genes far from zero, where squares lose the difference
+        let population = [individual(&[10000.0]), individual(&[10000.5])];
+        assert_relative_eq!(Statistics::new(&population).diversity, 0.25);
+    }
+
+    #[test]
+    fn history() {
+        let mut history = History::new();
+
+        assert!(history.is_empty());
+        assert_eq!(history.best_fitness(), None);
+
+        history.record(Statistics::new(&[individual(&[1.0]), individual(&[3.0])]));
+        history.record(Statistics::new(&[individual(&[5.0]), individual(&[2.0])]));
+        history.extend([Statistics::new(&[individual(&[4.0])])]);
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.best_fitness(), Some(5.0));
+        assert_eq!(history.last().unwrap().max_fitness, 4.0);
+        assert_eq!(history.series(|stats| stats.avg_fitness), [2.0, 3.5, 4.0]);
+        assert_eq!(
+            history.series(|stats| stats.best_index as f32),
+            [1.0, 0.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use lib_neural_network::{Activation, LayerTopology, Network, Scratch};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The bird brain's shape: eye cells, twice as many hidden neurons and
+/// two outputs (speed, rotation).
+fn topology(cells: usize) -> [LayerTopology; 3] {
+    [
+        LayerTopology {
+            neurons: cells,
+            ..Default::default()
+        },
+        LayerTopology {
+            neurons: 2 * cells,
+            activation: Activation::Relu,
+            ..Default::default()
+        },
+        LayerTopology {
+            neurons: 2,
+            activation: Activation::Tanh,
+            ..Default::default()
+        },
+    ]
+}
+
+/// The representation `Network` used before switching to flat weight
+/// matrices: every neuron owns its own `Vec` of weights and every layer
+/// allocates a fresh output `Vec`.
+mod per_neuron {
+    pub struct Network {
+        layers: Vec<Vec<Neuron>>,
+    }
+
+    struct Neuron {
+        bias: f32,
+        weights: Vec<f32>,
+    }
+
+    impl Network {
+        pub fn from_network(network: &lib_neural_network::Network) -> Self {
+            let topology = network.topology();
+            let mut weights = network.weights();
+
+            let layers = topology
+                .windows(2)
+                .map(|layers| {
+                    (0..layers[1].neurons)
+                        .map(|_| Neuron {
+                            bias: weights.next().unwrap(),
+                            weights: weights.by_ref().take(layers[0].neurons).collect(),
+                        })
+                        .collect()
+                })
+                .collect();
+
+            Self { layers }
+        }
+
+        pub fn propogate(&self, inputs: Vec<f32>) -> Vec<f32> {
+            let last = self.layers.len() - 1;
+
+            self.layers
+                .iter()
+                .enumerate()
+                .fold(inputs, |inputs, (idx, layer)| {
+                    layer
+                        .iter()
+                        .map(|neuron| {
+                            let output = inputs
+                                .iter()
+                                .zip(&neuron.weights)
+                                .map(|(input, weight)| input * weight)
+                                .sum::<f32>();
+
+                            let output = neuron.bias + output;
+
+                            if idx == last {
+                                output.tanh()
+                            } else {
+                                output.max(0.0)
+                            }
+                        })
+                        .collect()
+                })
+        }
+    }
+}
+
+fn propagation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("propagation");
+
+    for cells in [9, 25] {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = Network::random(&mut rng, &topology(cells));
+        let legacy = per_neuron::Network::from_network(&network);
+        let quantized = network.quantize();
+        let inputs: Vec<f32> = (0..cells).map(|_| rng.gen()).collect();
+        let mut scratch = Scratch::new();
+
+        group.bench_with_input(
+            BenchmarkId::new("per_neuron", cells),
+            &inputs,
+            |b, inputs| b.iter(|| legacy.propogate(black_box(inputs.clone()))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("propogate", cells),
+            &inputs,
+            |b, inputs| b.iter(|| network.propogate(black_box(inputs.clone()))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("propagate_into", cells),
+            &inputs,
+            |b, inputs| b.iter(|| network.propagate_into(black_box(inputs), &mut scratch)[0]),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("quantized", cells),
+            &inputs,
+            |b, inputs| b.iter(|| quantized.propagate_into(black_box(inputs), &mut scratch)[0]),
+        );
+    }
+
+    group.finish();
+}
+
+fn propagation_batch(c: &mut Criterion) {
+    const BIRDS: usize = 40;
+
+    let mut group = c.benchmark_group("propagation_batch");
+
+    for cells in [9, 25] {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = Network::random(&mut rng, &topology(cells));
+        let legacy = per_neuron::Network::from_network(&network);
+        let inputs: Vec<Vec<f32>> = (0..BIRDS)
+            .map(|_| (0..cells).map(|_| rng.gen()).collect())
+            .collect();
+        let batch = inputs.concat();
+        let mut scratch = Scratch::new();
+
+        group.bench_with_input(
+            BenchmarkId::new("per_neuron", cells),
+            &inputs,
+            |b, inputs| {
+                b.iter(|| {
+                    for inputs in inputs {
+                        black_box(legacy.propogate(inputs.clone()));
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("propagate_batch", cells),
+            &batch,
+            |b, batch| b.iter(|| network.propagate_batch(black_box(batch), &mut scratch)[0]),
+        );
+
+        // Each bird has its own brain
+        let brains: Vec<_> = (0..BIRDS)
+            .map(|_| Network::random(&mut rng, &topology(cells)))
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("propagate_into_each", cells),
+            &inputs,
+            |b, inputs| {
+                b.iter(|| {
+                    for (brain, inputs) in brains.iter().zip(inputs) {
+                        black_box(brain.propagate_into(black_box(inputs), &mut scratch)[0]);
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("propagate_many", cells),
+            &batch,
+            |b, batch| {
+                b.iter(|| Network::propagate_many(&brains, black_box(batch), &mut scratch)[0])
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, propagation, propagation_batch);
+criterion_main!(benches);
@@ -0,0 +1,321 @@
+use crate::Scalar;
+use serde::{Deserialize, Serialize};
+
+/// Function applied to a neuron's weighted sum (bias included) to
+/// produce its output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    /// `max(0, x)` - range `[0, inf)`
+    #[default]
+    Relu,
+
+    /// `x` for positive inputs, `alpha * x` otherwise - range `(-inf, inf)`
+    LeakyRelu { alpha: f32 },
+
+    /// `1 / (1 + e^-x)` - range `(0, 1)`
+    Sigmoid,
+
+    /// Hyperbolic tangent - range `(-1, 1)`
+    Tanh,
+
+    /// `x` - range `(-inf, inf)`
+    Identity,
+
+    /// `x / (1 + |x|)` - range `(-1, 1)`
+    Softsign,
+
+    /// `1` for non-negative inputs, `0` otherwise - range `{0, 1}`
+    Step,
+
+    /// `e^x_i / sum(e^x_j)` over all neurons of the layer (or of the output
+    /// head, see [`OutputHead`](crate::OutputHead)) - range `(0, 1)`, with outputs summing up
+    /// to one, e.g. probabilities of discrete actions.
+    ///
+    /// Applied to a lone number - by [`Self::apply()`] or by NEAT nodes -
+    /// it's a softmax over a single element, i.e. always `1`.
+    Softmax,
+}
+
+impl Activation {
+    pub fn apply(self, x: f32) -> f32 {
+        self.apply_to(x)
+    }
+
+    /// Like [`Self::apply()`], but for any [`Scalar`].
+    pub fn apply_to<T: Scalar>(self, x: T) -> T {
+        match self {
+            Self::Relu => x.max(T::zero()),
+            Self::LeakyRelu { alpha } => {
+                if x > T::zero() {
+                    x
+                } else {
+                    T::from_f64(alpha as f64) * x
+                }
+            }
+            Self::Sigmoid => T::one() / (T::one() + (-x).exp()),
+            Self::Tanh => x.tanh(),
+            Self::Identity => x,
+            Self::Softsign => x / (T::one() + x.abs()),
+            Self::Step => {
+                if x >= T::zero() {
+                    T::one()
+                } else {
+                    T::zero()
+                }
+            }
+            Self::Softmax => T::one(),
+        }
+    }
+
+    /// Applies activation to all outputs of a layer (or of an output head)
+    /// for a single sample - the same as calling [`Self::apply_to()`] on
+    /// each of them, except for [`Self::Softmax`], which normalizes them
+    /// together.
+    pub fn apply_all<T: Scalar>(self, values: &mut [T]) {
+        if self != Self::Softmax {
+            for value in values {
+                *value = self.apply_to(*value);
+            }
+
+            return;
+        }
+
+        let Some(&first) = values.first() else {
+            return;
+        };
+
+        // Shifting by the maximum doesn't change the result, but keeps
+        // `exp()` from overflowing
+        let max = values.iter().fold(first, |max, &x| max.max(x));
+        let mut sum = T::zero();
+
+        for value in values.iter_mut() {
+            *value = (*value - max).exp();
+            sum += *value;
+        }
+
+        for value in values {
+            *value = *value / sum;
+        }
+    }
+
+    /// Turns `gradients` of loss with respect to outputs of
+    /// [`Self::apply_all()`] into gradients with respect to its inputs,
+    /// given both.
+    pub(crate) fn backpropagate(self, inputs: &[f32], outputs: &[f32], gradients: &mut [f32]) {
+        if self == Self::Softmax {
+            // dy_i / dx_j = y_i * ([i == j] - y_j)
+            let dot: f32 = outputs.iter().zip(&*gradients).map(|(y, g)| y * g).sum();
+
+            for (gradient, y) in gradients.iter_mut().zip(outputs) {
+                *gradient = y * (*gradient - dot);
+            }
+        } else {
+            for (gradient, &x) in gradients.iter_mut().zip(inputs) {
+                *gradient *= self.derivative(x);
+            }
+        }
+    }
+
+    /// Derivative of [`Self::apply()`] at `x`, as used by backpropagation.
+    ///
+    /// `Relu` and `Step` aren't differentiable at zero, where they're given
+    /// derivative of `0`; `Softmax` of a lone number is constant.
+    pub fn derivative(self, x: f32) -> f32 {
+        match self {
+            Self::Relu => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::LeakyRelu { alpha } => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    alpha
+                }
+            }
+            Self::Sigmoid => {
+                let y = self.apply(x);
+
+                y * (1.0 - y)
+            }
+            Self::Tanh => 1.0 - x.tanh().powi(2),
+            Self::Identity => 1.0,
+            Self::Softsign => 1.0 / (1.0 + x.abs()).powi(2),
+            Self::Step | Self::Softmax => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const INPUTS: [f32; 9] = [-1000.0, -10.0, -1.0, -0.5, 0.0, 0.5, 1.0, 10.0, 1000.0];
+
+    fn outputs(activation: Activation) -> Vec<f32> {
+        INPUTS.iter().map(|&x| activation.apply(x)).collect()
+    }
+
+    #[test]
+    fn relu() {
+        let actual = outputs(Activation::Relu);
+
+        assert!(actual.iter().all(|&y| y >= 0.0));
+        assert_relative_eq!(Activation::Relu.apply(-0.5), 0.0);
+        assert_relative_eq!(Activation::Relu.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn leaky_relu() {
+        let activation = Activation::LeakyRelu { alpha: 0.01 };
+        let actual = outputs(activation);
+
+        assert!(actual.iter().any(|&y| y < 0.0));
+        assert_relative_eq!(activation.apply(-10.0), -0.1);
+        assert_relative_eq!(activation.apply(10.0), 10.0);
+    }
+
+    #[test]
+    fn sigmoid() {
+        let actual = outputs(Activation::Sigmoid);
+
+        assert!(actual.iter().all(|&y| (0.0..=1.0).contains(&y)));
+        assert_relative_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+    }
+
+    #[test]
+    fn tanh() {
+        let actual = outputs(Activation::Tanh);
+
+        assert!(actual.iter().all(|&y| (-1.0..=1.0).contains(&y)));
+        assert!(Activation::Tanh.apply(-1.0) < 0.0);
+        assert_relative_eq!(Activation::Tanh.apply(0.0), 0.0);
+    }
+
+    #[test]
+    fn identity() {
+        let actual = outputs(Activation::Identity);
+
+        assert_relative_eq!(actual.as_slice(), INPUTS.as_ref());
+    }
+
+    #[test]
+    fn softsign() {
+        let actual = outputs(Activation::Softsign);
+
+        assert!(actual.iter().all(|&y| y > -1.0 && y < 1.0));
+        assert_relative_eq!(Activation::Softsign.apply(1.0), 0.5);
+        assert_relative_eq!(Activation::Softsign.apply(-1.0), -0.5);
+    }
+
+    #[test]
+    fn step() {
+        let actual = outputs(Activation::Step);
+
+        assert_relative_eq!(
+            actual.as_slice(),
+            [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn derivative() {
+        let activations = [
+            Activation::Relu,
+            Activation::LeakyRelu { alpha: 0.01 },
+            Activation::Sigmoid,
+            Activation::Tanh,
+            Activation::Identity,
+            Activation::Softsign,
+            Activation::Step,
+            Activation::Softmax,
+        ];
+
+        let h = 1e-3;
+
+        // Away from zero, where Relu & co. have a kink
+        for x in [-2.0, -0.5, 0.5, 2.0] {
+            for activation in activations {
+                let expected = (activation.apply(x + h) - activation.apply(x - h)) / (2.0 * h);
+
+                assert_relative_eq!(activation.derivative(x), expected, epsilon = 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn softmax() {
+        let mut values = [1.0, 2.0, 3.0];
+
+        Activation::Softmax.apply_all(&mut values);
+
+        let sum = 1.0 + 1.0f32.exp() + 2.0f32.exp();
+        let expected = [1.0 / sum, 1.0f32.exp() / sum, 2.0f32.exp() / sum];
+
+        assert_relative_eq!(values.as_ref(), expected.as_ref());
+
+        // Large inputs don't overflow
+        let mut values = [1000.0, 1000.0];
+
+        Activation::Softmax.apply_all(&mut values);
+
+        assert_relative_eq!(values.as_ref(), [0.5, 0.5].as_ref());
+        assert_relative_eq!(Activation::Softmax.apply(5.0), 1.0);
+    }
+
+    #[test]
+    fn apply_all() {
+        let mut actual = INPUTS;
+
+        Activation::Tanh.apply_all(&mut actual);
+
+        let expected = outputs(Activation::Tanh);
+
+        assert_relative_eq!(actual.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn backpropagate() {
+        let inputs = [0.5, -1.0, 2.0];
+
+        // Loss = sum(weights * outputs)
+        let weights = [1.0, -2.0, 0.5];
+        let h = 1e-2;
+
+        for activation in [Activation::Softmax, Activation::Sigmoid] {
+            let loss = |inputs: [f32; 3]| {
+                let mut outputs = inputs;
+
+                activation.apply_all(&mut outputs);
+                outputs
+                    .iter()
+                    .zip(&weights)
+                    .map(|(y, w)| y * w)
+                    .sum::<f32>()
+            };
+
+            let mut outputs = inputs;
+            let mut gradients = weights;
+
+            activation.apply_all(&mut outputs);
+            activation.backpropagate(&inputs, &outputs, &mut gradients);
+
+            for (idx, gradient) in gradients.iter().enumerate() {
+                let (mut plus, mut minus) = (inputs, inputs);
+
+                plus[idx] += h;
+                minus[idx] -= h;
+
+                let expected = (loss(plus) - loss(minus)) / (2.0 * h);
+
+                assert_relative_eq!(*gradient, expected, epsilon = 1e-3);
+            }
+        }
+    }
+}
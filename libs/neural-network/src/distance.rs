@@ -0,0 +1,295 @@
+use crate::*;
+
+/// How far apart two networks' weights are, treating all of a network's
+/// weights and biases (in the order [`Network::weights()`] yields them) as
+/// a single vector.
+///
+/// ```
+/// # use lib_neural_network::*;
+/// let layers = [
+///     LayerTopology { neurons: 1, ..Default::default() },
+///     LayerTopology { neurons: 1, ..Default::default() },
+/// ];
+///
+/// let a = Network::from_weights(&layers, [0.0, 1.0]);
+/// let b = Network::from_weights(&layers, [3.0, 5.0]);
+///
+/// assert_eq!(a.weight_distance(&b, WeightDistance::L1), 7.0);
+/// assert_eq!(a.weight_distance(&b, WeightDistance::L2), 5.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightDistance {
+    /// Sum of absolute differences
+    L1,
+
+    /// Euclidean distance
+    L2,
+
+    /// `1 - cos(angle between the vectors)` - range `[0, 2]`; ignores
+    /// magnitudes, so a network and its scaled copy are at distance zero.
+    ///
+    /// Zero vector is at distance `1` from everything but another zero
+    /// vector.
+    Cosine,
+}
+
+impl WeightDistance {
+    /// Distance between two equally long vectors - e.g. networks' weights
+    /// or chromosomes built from them.
+    pub fn between(self, a: &[f32], b: &[f32]) -> f32 {
+        assert_eq!(a.len(), b.len(), "vectors must be of the same length");
+
+        let pairs = a.iter().zip(b);
+
+        match self {
+            Self::L1 => pairs.map(|(a, b)| (a - b).abs()).sum(),
+            Self::L2 => pairs.map(|(a, b)| (a - b).powi(2)).sum::<f32>().sqrt(),
+            Self::Cosine => {
+                let (dot, norm_a, norm_b) = pairs.fold((0.0, 0.0, 0.0), |(dot, na, nb), (a, b)| {
+                    (dot + a * b, na + a * a, nb + b * b)
+                });
+
+                match (norm_a == 0.0, norm_b == 0.0) {
+                    (true, true) => 0.0,
+                    (true, false) | (false, true) => 1.0,
+                    (false, false) => {
+                        let similarity: f32 = dot / (norm_a.sqrt() * norm_b.sqrt());
+
+                        1.0 - similarity.clamp(-1.0, 1.0)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Network {
+    /// Distance between this and the other network's weights; panics if
+    /// the networks have different topologies.
+    pub fn weight_distance(&self, other: &Network, metric: WeightDistance) -> f32 {
+        self.try_weight_distance(other, metric)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_weight_distance(
+        &self,
+        other: &Network,
+        metric: WeightDistance,
+    ) -> Result<f32, NetworkError> {
+        self.check_same_topology(other)?;
+
+        let a: Vec<_> = self.weights().collect();
+        let b: Vec<_> = other.weights().collect();
+
+        Ok(metric.between(&a, &b))
+    }
+
+    /// How differently the networks behave: average Euclidean distance
+    /// between their outputs over samples laid out one after another in
+    /// `inputs` (as in [`Self::propagate_batch()`]).
+    ///
+    /// Networks can have different topologies, as long as their input and
+    /// output layers are of the same size; recurrent layers start from
+    /// zero state for each sample.
+    pub fn behavioural_distance(&self, other: &Network, inputs: &[f32]) -> f32 {
+        self.try_behavioural_distance(other, inputs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_behavioural_distance(
+        &self,
+        other: &Network,
+        inputs: &[f32],
+    ) -> Result<f32, NetworkError> {
+        let output_size = self.output_size();
+
+        if self.layers[0].input_size != other.layers[0].input_size
+            || output_size != other.output_size()
+        {
+            return Err(NetworkError::TopologyMismatch);
+        }
+
+        let mut scratch = Scratch::default();
+        let a = self.propagate_samples(inputs, &mut scratch).to_vec();
+        let b = other.propagate_samples(inputs, &mut scratch);
+
+        if a.is_empty() || output_size == 0 {
+            return Ok(0.0);
+        }
+
+        let samples = a.len() / output_size;
+
+        let total: f32 = a
+            .chunks_exact(output_size)
+            .zip(b.chunks_exact(output_size))
+            .map(|(a, b)| WeightDistance::L2.between(a, b))
+            .sum();
+
+        Ok(total / samples as f32)
+    }
+
+    fn check_same_topology(&self, other: &Network) -> Result<(), NetworkError> {
+        let ours = self.topology();
+        let theirs = other.topology();
+
+        let same = ours.len() == theirs.len()
+            && ours
+                .iter()
+                .zip(&theirs)
+                .all(|(a, b)| a.neurons == b.neurons && a.kind == b.kind);
+
+        if same {
+            Ok(())
+        } else {
+            Err(NetworkError::TopologyMismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    mod between {
+        use super::*;
+
+        const A: [f32; 3] = [1.0, 2.0, -1.0];
+        const B: [f32; 3] = [2.0, 0.0, -1.0];
+
+        #[test]
+        fn l1() {
+            assert_relative_eq!(WeightDistance::L1.between(&A, &B), 3.0);
+        }
+
+        #[test]
+        fn l2() {
+            assert_relative_eq!(WeightDistance::L2.between(&A, &B), 5.0f32.sqrt());
+        }
+
+        #[test]
+        fn cosine() {
+            // cos = (2 + 0 + 1) / (sqrt(6) * sqrt(5))
+            assert_relative_eq!(
+                WeightDistance::Cosine.between(&A, &B),
+                1.0 - 3.0 / 30.0f32.sqrt()
+            );
+
+            let scaled = A.map(|x| 3.0 * x);
+            let negated = A.map(|x| -x);
+
+            assert_relative_eq!(WeightDistance::Cosine.between(&A, &scaled), 0.0);
+            assert_relative_eq!(WeightDistance::Cosine.between(&A, &negated), 2.0);
+        }
+
+        #[test]
+        fn cosine_of_zero_vectors() {
+            let zero = [0.0; 3];
+
+            assert_relative_eq!(WeightDistance::Cosine.between(&zero, &zero), 0.0);
+            assert_relative_eq!(WeightDistance::Cosine.between(&zero, &A), 1.0);
+        }
+
+        #[test]
+        fn same_vectors() {
+            for metric in [
+                WeightDistance::L1,
+                WeightDistance::L2,
+                WeightDistance::Cosine,
+            ] {
+                assert_relative_eq!(metric.between(&A, &A), 0.0, epsilon = 1e-6);
+            }
+        }
+    }
+
+    fn topology(hidden: usize) -> [LayerTopology; 3] {
+        [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: hidden,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn weight_distance() {
+        let a = Network::from_weights(&topology(1), [0.0, 1.0, 1.0, 0.0, 1.0]);
+        let b = Network::from_weights(&topology(1), [0.0, 1.0, -1.0, 0.5, 1.0]);
+
+        assert_relative_eq!(a.weight_distance(&b, WeightDistance::L1), 2.5);
+        assert_relative_eq!(a.weight_distance(&b, WeightDistance::L2), 4.25f32.sqrt());
+    }
+
+    #[test]
+    fn try_weight_distance() {
+        let a = Network::from_weights(&topology(1), [0.0; 5]);
+        let b = Network::from_weights(&topology(2), [0.0; 9]);
+
+        assert_eq!(
+            a.try_weight_distance(&b, WeightDistance::L2),
+            Err(NetworkError::TopologyMismatch)
+        );
+    }
+
+    #[test]
+    fn behavioural_distance() {
+        // x0 + x1
+        let a = Network::from_weights(&topology(1), [0.0, 1.0, 1.0, 0.0, 1.0]);
+
+        // x0 - x1, computed by a differently shaped network
+        let b = Network::from_weights(&topology(2), [0.0, 1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 1.0, 1.0]);
+
+        let inputs = [1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0];
+
+        // Outputs differ by 0, 2, 2 and 0
+        assert_relative_eq!(a.behavioural_distance(&b, &inputs), 1.0);
+        assert_relative_eq!(a.behavioural_distance(&a, &inputs), 0.0);
+    }
+
+    #[test]
+    fn behavioural_distance_ignores_weights_that_dont_matter() {
+        // Hidden neuron 1 isn't connected to the output
+        let a = Network::from_weights(&topology(2), [0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        let b = Network::from_weights(&topology(2), [0.0, 1.0, 1.0, 5.0, 5.0, 5.0, 0.0, 1.0, 0.0]);
+
+        let inputs = [1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+
+        assert!(a.weight_distance(&b, WeightDistance::L2) > 0.0);
+        assert_relative_eq!(a.behavioural_distance(&b, &inputs), 0.0);
+    }
+
+    #[test]
+    fn try_behavioural_distance() {
+        let a = Network::from_weights(&topology(1), [0.0; 5]);
+
+        let b = Network::from_weights(
+            &[
+                LayerTopology {
+                    neurons: 3,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    ..Default::default()
+                },
+            ],
+            [0.0; 4],
+        );
+
+        assert_eq!(
+            a.try_behavioural_distance(&b, &[0.0, 0.0]),
+            Err(NetworkError::TopologyMismatch)
+        );
+    }
+}
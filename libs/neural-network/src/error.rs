@@ -0,0 +1,102 @@
+use std::{error, fmt};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkError {
+    /// Topology has fewer than two layers (input + output)
+    EmptyTopology,
+
+    /// Got fewer weights than the topology requires
+    TooFewWeights { expected: usize, actual: usize },
+
+    /// Got more weights than the topology requires
+    TooManyWeights { expected: usize, actual: usize },
+
+    /// Topology requires more weights than can be counted
+    TopologyTooLarge,
+
+    /// Given layer's initializer can't be sampled - e.g. its range is
+    /// empty
+    InvalidInitializer { layer: usize },
+
+    /// Number of inputs doesn't match the size of the input layer
+    InputLengthMismatch { expected: usize, actual: usize },
+
+    /// Quantization doesn't support recurrent layers
+    RecurrentQuantization,
+
+    /// Batched propagation doesn't support recurrent layers
+    RecurrentBatch,
+
+    /// Networks being compared are of incompatible shapes
+    TopologyMismatch,
+
+    /// Only the output layer can be split into heads
+    HeadsOnHiddenLayer,
+
+    /// Output heads don't add up to the output layer
+    HeadSizeMismatch { expected: usize, actual: usize },
+
+    /// Two output heads share a name
+    DuplicateHead { name: String },
+
+    /// Genome's nodes aren't sorted by id, or some ids repeat
+    UnorderedNodes,
+
+    /// Genome's connection leads from or to a node it doesn't have
+    UnknownNode { id: usize },
+
+    /// Genome's enabled connections form a cycle
+    CyclicGenome,
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyTopology => write!(
+                f,
+                "network topology must have at least two layers (input + output)"
+            ),
+            Self::TooFewWeights { expected, actual } => write!(
+                f,
+                "got not enough weights: topology requires {expected}, got {actual}"
+            ),
+            Self::TooManyWeights { expected, actual } => write!(
+                f,
+                "got too many weights: topology requires {expected}, got {actual}"
+            ),
+            Self::TopologyTooLarge => {
+                write!(f, "network topology requires too many weights")
+            }
+            Self::InvalidInitializer { layer } => {
+                write!(f, "layer {layer} has an invalid initializer")
+            }
+            Self::InputLengthMismatch { expected, actual } => {
+                write!(f, "got {actual} inputs, but network expects {expected}")
+            }
+            Self::RecurrentQuantization => {
+                write!(f, "networks with recurrent layers cannot be quantized")
+            }
+            Self::RecurrentBatch => {
+                write!(f, "networks with recurrent layers cannot be batched")
+            }
+            Self::TopologyMismatch => {
+                write!(f, "networks have incompatible topologies")
+            }
+            Self::HeadsOnHiddenLayer => {
+                write!(f, "only the output layer can have heads")
+            }
+            Self::HeadSizeMismatch { expected, actual } => write!(
+                f,
+                "output heads have {actual} neurons in total, but output layer has {expected}"
+            ),
+            Self::DuplicateHead { name } => write!(f, "duplicate output head `{name}`"),
+            Self::UnorderedNodes => {
+                write!(f, "genome's nodes must be sorted by id, without duplicates")
+            }
+            Self::UnknownNode { id } => write!(f, "genome has a connection to unknown node {id}"),
+            Self::CyclicGenome => write!(f, "genome contains a cycle"),
+        }
+    }
+}
+
+impl error::Error for NetworkError {}
@@ -0,0 +1,252 @@
+use crate::*;
+use std::ops::Range;
+
+/// Named slice of the output layer with an activation of its own - e.g. two
+/// `Tanh` neurons for steering next to three `Softmax` ones picking a
+/// discrete action.
+///
+/// Heads are listed in the output layer's [`LayerTopology::heads`], in the
+/// order their neurons come in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutputHead {
+    pub name: String,
+    pub neurons: usize,
+
+    /// Overrides the layer's activation; [`Activation::Softmax`] normalizes
+    /// over this head only.
+    pub activation: Activation,
+}
+
+impl OutputHead {
+    pub fn new(name: impl Into<String>, neurons: usize, activation: Activation) -> Self {
+        Self {
+            name: name.into(),
+            neurons,
+            activation,
+        }
+    }
+}
+
+impl<T: Scalar> Network<T> {
+    /// Heads of the output layer, in the order their outputs come in;
+    /// empty if the network was built without any.
+    pub fn heads(&self) -> &[OutputHead] {
+        &self.layers[self.layers.len() - 1].heads
+    }
+
+    /// Which of the network's outputs belong to given head.
+    pub fn head_range(&self, name: &str) -> Option<Range<usize>> {
+        let layer = &self.layers[self.layers.len() - 1];
+
+        segments(layer.activation, &layer.heads, layer.output_size())
+            .zip(&layer.heads)
+            .find(|(_, head)| head.name == name)
+            .map(|((range, _), _)| range)
+    }
+
+    /// Given head's part of `outputs` (as returned by e.g.
+    /// [`Self::propagate_into()`]).
+    pub fn head<'a>(&self, outputs: &'a [T], name: &str) -> Option<&'a [T]> {
+        self.head_range(name).map(|range| &outputs[range])
+    }
+}
+
+/// Ranges of a layer's outputs that get activated together, each with its
+/// activation: one per head or, for layers without heads, a single one
+/// spanning all of the outputs.
+pub(crate) fn segments(
+    activation: Activation,
+    heads: &[OutputHead],
+    outputs: usize,
+) -> impl Iterator<Item = (Range<usize>, Activation)> + '_ {
+    let whole = heads.is_empty().then_some((0..outputs, activation));
+    let mut start = 0;
+
+    whole.into_iter().chain(heads.iter().map(move |head| {
+        let range = start..start + head.neurons;
+
+        start = range.end;
+
+        (range, head.activation)
+    }))
+}
+
+/// Checks that only the output layer has heads and that they cover it.
+pub(crate) fn check_heads(layers: &[LayerTopology]) -> Result<(), NetworkError> {
+    let (output, hidden) = layers.split_last().ok_or(NetworkError::EmptyTopology)?;
+
+    if hidden.iter().any(|layer| !layer.heads.is_empty()) {
+        return Err(NetworkError::HeadsOnHiddenLayer);
+    }
+
+    if output.heads.is_empty() {
+        return Ok(());
+    }
+
+    let actual = output
+        .heads
+        .iter()
+        .try_fold(0usize, |sum, head| sum.checked_add(head.neurons))
+        .ok_or(NetworkError::TopologyTooLarge)?;
+
+    if actual != output.neurons {
+        return Err(NetworkError::HeadSizeMismatch {
+            expected: output.neurons,
+            actual,
+        });
+    }
+
+    for (idx, head) in output.heads.iter().enumerate() {
+        if output.heads[..idx]
+            .iter()
+            .any(|other| other.name == head.name)
+        {
+            return Err(NetworkError::DuplicateHead {
+                name: head.name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn layers() -> [LayerTopology; 2] {
+        [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 5,
+                heads: vec![
+                    OutputHead::new("steering", 2, Activation::Tanh),
+                    OutputHead::new("action", 3, Activation::Softmax),
+                ],
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn network() -> Network {
+        Network::from_weights(
+            &layers(),
+            [
+                // Steering
+                0.0, 1.0, 0.0,
+                0.5, 0.0, -1.0,
+                // Action
+                0.0, 1.0, 1.0,
+                1.0, 0.0, 0.0,
+                0.0, 0.0, 0.0,
+            ],
+        )
+    }
+
+    #[test]
+    fn propogate() {
+        let outputs = network().propogate(vec![1.0, 2.0]);
+
+        assert_relative_eq!(outputs[0], 1.0f32.tanh());
+        assert_relative_eq!(outputs[1], (-1.5f32).tanh());
+
+        // Softmax of [3, 1, 0]
+        let sum = 3.0f32.exp() + 1.0f32.exp() + 1.0;
+        let expected = [3.0f32.exp() / sum, 1.0f32.exp() / sum, 1.0 / sum];
+
+        assert_relative_eq!(&outputs[2..], expected.as_ref());
+    }
+
+    #[test]
+    fn propagate_batch() {
+        let network = network();
+        let inputs = [1.0, 2.0, -1.0, 0.5];
+
+        let expected: Vec<_> = inputs
+            .chunks(2)
+            .flat_map(|inputs| network.propogate(inputs.to_vec()))
+            .collect();
+
+        let mut scratch = Scratch::new();
+        let actual = network.propagate_batch(&inputs, &mut scratch);
+
+        assert_relative_eq!(actual, expected.as_slice());
+    }
+
+    #[test]
+    fn head_range() {
+        let network = network();
+
+        assert_eq!(network.heads(), &layers()[1].heads[..]);
+        assert_eq!(network.head_range("steering"), Some(0..2));
+        assert_eq!(network.head_range("action"), Some(2..5));
+        assert_eq!(network.head_range("missing"), None);
+
+        let outputs = network.propogate(vec![1.0, 2.0]);
+        let action = network.head(&outputs, "action").unwrap();
+
+        assert_eq!(action.len(), 3);
+        assert_relative_eq!(action.iter().sum::<f32>(), 1.0);
+    }
+
+    #[test]
+    fn without_heads() {
+        let mut layers = layers();
+
+        layers[1].heads.clear();
+
+        let network = Network::from_weights(&layers, [0.0; 15]);
+
+        assert!(network.heads().is_empty());
+        assert_eq!(network.head_range("steering"), None);
+    }
+
+    #[test]
+    fn check_heads() {
+        let mut layers = layers();
+
+        layers[1].heads[1].neurons = 2;
+
+        assert_eq!(
+            Network::try_from_weights(&layers, [0.0; 15]).unwrap_err(),
+            NetworkError::HeadSizeMismatch {
+                expected: 5,
+                actual: 4
+            }
+        );
+
+        let mut layers = self::layers();
+
+        layers[1].heads[1].name = "steering".into();
+
+        assert_eq!(
+            Network::try_from_weights(&layers, [0.0; 15]).unwrap_err(),
+            NetworkError::DuplicateHead {
+                name: "steering".into()
+            }
+        );
+
+        let mut layers = self::layers();
+
+        layers[0].heads = layers[1].heads.clone();
+
+        assert_eq!(
+            Network::try_from_weights(&layers, [0.0; 15]).unwrap_err(),
+            NetworkError::HeadsOnHiddenLayer
+        );
+
+        let mut layers = self::layers();
+
+        layers[1].heads[0].neurons = usize::MAX;
+
+        assert_eq!(
+            Network::try_from_weights(&layers, [0.0; 15]).unwrap_err(),
+            NetworkError::TopologyTooLarge
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use crate::*;
+use rand_distr::{Distribution, Normal};
+
+/// How [`Network::random()`] picks a layer's starting weights and biases.
+///
+/// `fan_in` is the number of values each neuron receives (its inputs,
+/// plus the layer's previous outputs for recurrent layers), `fan_out` is
+/// the number of neurons in the layer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Initializer {
+    /// Weights and biases drawn uniformly from `[low, high]`
+    Uniform { low: f32, high: f32 },
+
+    /// Xavier/Glorot: weights drawn uniformly from `[-limit, limit]`,
+    /// where `limit = sqrt(6 / (fan_in + fan_out))`; zero biases.
+    ///
+    /// Keeps outputs of tanh/sigmoid layers from saturating, no matter
+    /// how many inputs there are.
+    Xavier,
+
+    /// He/Kaiming: weights drawn from normal distribution with standard
+    /// deviation of `sqrt(2 / fan_in)`; zero biases.
+    ///
+    /// Xavier's counterpart for ReLU layers.
+    He,
+
+    /// Weights and biases drawn from normal distribution with mean of zero
+    /// and given standard deviation
+    Normal { std: f32 },
+
+    /// All weights and biases set to zero
+    Zeros,
+}
+
+impl Default for Initializer {
+    fn default() -> Self {
+        Self::Uniform {
+            low: -1.0,
+            high: 1.0,
+        }
+    }
+}
+
+/// What kind of number is being initialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Param {
+    Bias,
+    Weight,
+}
+
+impl Initializer {
+    /// Whether the distribution can be sampled - a finite range with
+    /// `low <= high` for [`Self::Uniform`], a finite, non-negative
+    /// deviation for [`Self::Normal`].
+    pub(crate) fn is_valid(self) -> bool {
+        match self {
+            Self::Uniform { low, high } => low <= high && (high - low).is_finite(),
+            Self::Normal { std } => std.is_finite() && std >= 0.0,
+            Self::Xavier | Self::He | Self::Zeros => true,
+        }
+    }
+
+    pub(crate) fn sample(
+        self,
+        rng: &mut dyn RngCore,
+        param: Param,
+        fan_in: usize,
+        fan_out: usize,
+    ) -> f32 {
+        match (self, param) {
+            (Self::Uniform { low, high }, _) => rng.gen_range(low..=high),
+
+            (Self::Xavier, Param::Weight) => {
+                let limit = (6.0 / (fan_in + fan_out).max(1) as f32).sqrt();
+
+                rng.gen_range(-limit..=limit)
+            }
+
+            (Self::He, Param::Weight) => {
+                let std = (2.0 / fan_in.max(1) as f32).sqrt();
+
+                normal(rng, std)
+            }
+
+            (Self::Normal { std }, _) => normal(rng, std),
+
+            (Self::Xavier | Self::He, Param::Bias) | (Self::Zeros, _) => 0.0,
+        }
+    }
+}
+
+fn normal(rng: &mut dyn RngCore, std: f32) -> f32 {
+    Normal::new(0.0, std)
+        .expect("standard deviation must be finite and non-negative")
+        .sample(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Initializes a 4x2 layer using given scheme, returning its biases and
+    /// weights.
+    fn actual(initializer: Initializer) -> (Vec<f32>, Vec<f32>) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 4,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 2,
+                    initializer,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let layer = &network.layers[0];
+
+        (layer.biases.clone(), layer.weights.clone())
+    }
+
+    #[test]
+    fn uniform() {
+        let (biases, weights) = actual(Initializer::Uniform {
+            low: 0.0,
+            high: 0.5,
+        });
+
+        assert_relative_eq!(biases.as_slice(), [0.09362031, 0.11620791].as_ref());
+        assert_relative_eq!(
+            weights.as_slice(),
+            [
+                0.4184599, 0.45453155, 0.31571224, 0.38097018, 0.26734242, 0.05879546, 0.22437516,
+                0.12780096
+            ]
+            .as_ref()
+        );
+    }
+
+    #[test]
+    fn uniform_by_default() {
+        let (biases, weights) = actual(Initializer::default());
+
+        // Same numbers as in the `layer::tests::random` test
+        assert_relative_eq!(biases[0], -0.6255188);
+        assert_relative_eq!(
+            &weights[..4],
+            [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
+        );
+    }
+
+    #[test]
+    fn xavier() {
+        let (biases, weights) = actual(Initializer::Xavier);
+        // fan_in = 4, fan_out = 2
+        let limit = (6.0f32 / 6.0).sqrt();
+
+        assert_relative_eq!(biases.as_slice(), [0.0, 0.0].as_ref());
+        assert_relative_eq!(
+            weights.as_slice(),
+            [
+                -0.6255188,
+                0.67383957,
+                0.8181262,
+                0.26284897,
+                0.5238807,
+                -0.53516835,
+                0.069369674,
+                -0.7648182
+            ]
+            .as_ref()
+        );
+        assert!(weights.iter().all(|w| w.abs() <= limit));
+    }
+
+    #[test]
+    fn he() {
+        let (biases, weights) = actual(Initializer::He);
+
+        assert_relative_eq!(biases.as_slice(), [0.0, 0.0].as_ref());
+        assert_relative_eq!(
+            weights.as_slice(),
+            [
+                0.974179,
+                0.28662348,
+                -0.84588987,
+                -1.3682848,
+                -0.47501576,
+                -0.7527971,
+                -1.5195557,
+                -0.7089185
+            ]
+            .as_ref()
+        );
+    }
+
+    #[test]
+    fn normal() {
+        let (biases, weights) = actual(Initializer::Normal { std: 0.1 });
+
+        assert_relative_eq!(biases.as_slice(), [0.13776973, -0.1064616].as_ref());
+        assert_relative_eq!(
+            weights.as_slice(),
+            [
+                0.040534683,
+                -0.119626895,
+                -0.1935047,
+                -0.06717738,
+                -0.21489763,
+                -0.10025622,
+                -0.03445154,
+                0.03935525
+            ]
+            .as_ref()
+        );
+    }
+
+    #[test]
+    fn zeros() {
+        let (biases, weights) = actual(Initializer::Zeros);
+
+        assert_relative_eq!(biases.as_slice(), [0.0; 2].as_ref());
+        assert_relative_eq!(weights.as_slice(), [0.0; 8].as_ref());
+    }
+
+    #[test]
+    fn invalid() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for initializer in [
+            Initializer::Uniform {
+                low: 1.0,
+                high: -1.0,
+            },
+            Initializer::Uniform {
+                low: 0.0,
+                high: f32::INFINITY,
+            },
+            Initializer::Uniform {
+                low: f32::MIN,
+                high: f32::MAX,
+            },
+            Initializer::Normal { std: -1.0 },
+            Initializer::Normal { std: f32::NAN },
+        ] {
+            let layers = [
+                LayerTopology {
+                    neurons: 4,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    initializer,
+                    ..Default::default()
+                },
+            ];
+
+            assert_eq!(
+                Network::try_random(&mut rng, &layers).unwrap_err(),
+                NetworkError::InvalidInitializer { layer: 2 },
+                "{initializer:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn he_scales_with_fan_in() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let std = |fan_in: usize, rng: &mut dyn RngCore| {
+            let samples: Vec<_> = (0..10_000)
+                .map(|_| Initializer::He.sample(rng, Param::Weight, fan_in, 1))
+                .collect();
+
+            let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+            let variance =
+                samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / samples.len() as f32;
+
+            variance.sqrt()
+        };
+
+        assert_relative_eq!(std(2, &mut rng), 1.0, epsilon = 0.02);
+        assert_relative_eq!(std(200, &mut rng), 0.1, epsilon = 0.002);
+    }
+}
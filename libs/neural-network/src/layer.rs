@@ -0,0 +1,508 @@
+use crate::*;
+
+/// Single layer of neurons, stored as a row-major weight matrix (one row
+/// per neuron, one column per input) plus a bias vector.
+#[derive(Clone, Debug)]
+pub(crate) struct Layer<T = f32> {
+    pub(crate) input_size: usize,
+    pub(crate) weights: Vec<T>,
+    pub(crate) biases: Vec<T>,
+    pub(crate) activation: Activation,
+
+    /// See [`LayerTopology::heads`]
+    pub(crate) heads: Vec<OutputHead>,
+
+    /// Present for [`LayerKind::Recurrent`] layers
+    pub(crate) recurrence: Option<Recurrence<T>>,
+}
+
+/// Elman-style feedback: each neuron additionally sees the layer's own
+/// outputs from the previous propagation.
+///
+/// Those outputs aren't stored here, but in the [`Scratch`] the network is
+/// propagated with - so that propagation doesn't need `&mut self`.
+#[derive(Clone, Debug)]
+pub(crate) struct Recurrence<T = f32> {
+    /// Row-major, one row per neuron, one column per previous output
+    pub(crate) weights: Vec<T>,
+}
+
+impl<T: Scalar> Layer<T> {
+    pub(crate) fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        topology: &LayerTopology,
+    ) -> Self {
+        let fan_in = match topology.kind {
+            LayerKind::Dense => input_size,
+            LayerKind::Recurrent => input_size + topology.neurons,
+        };
+
+        // Numbers are always drawn as `f32`, so that given seed yields the
+        // same network no matter the precision
+        Self::build(input_size, topology, |param| {
+            let value = topology
+                .initializer
+                .sample(rng, param, fan_in, topology.neurons);
+
+            T::from_f64(value as f64)
+        })
+    }
+
+    pub(crate) fn from_weights(
+        input_size: usize,
+        topology: &LayerTopology,
+        weights: &mut dyn Iterator<Item = T>,
+    ) -> Self {
+        Self::build(input_size, topology, |_| {
+            weights.next().expect("got not enough weights")
+        })
+    }
+
+    /// Builds layer by pulling numbers from `next` neuron-by-neuron, in
+    /// the order [`Self::weights()`] yields them - so that given seed
+    /// always yields the same network as `from_weights()` would build from
+    /// the same numbers.
+    fn build(
+        input_size: usize,
+        topology: &LayerTopology,
+        mut next: impl FnMut(Param) -> T,
+    ) -> Self {
+        let output_size = topology.neurons;
+        let recurrent = topology.kind == LayerKind::Recurrent;
+
+        let mut weights = Vec::with_capacity(input_size * output_size);
+        let mut biases = Vec::with_capacity(output_size);
+        let mut recurrent_weights = Vec::new();
+
+        for _ in 0..output_size {
+            biases.push(next(Param::Bias));
+            weights.extend((0..input_size).map(|_| next(Param::Weight)));
+
+            if recurrent {
+                recurrent_weights.extend((0..output_size).map(|_| next(Param::Weight)));
+            }
+        }
+
+        Self {
+            input_size,
+            weights,
+            biases,
+            activation: topology.activation,
+            heads: topology.heads.clone(),
+            recurrence: recurrent.then_some(Recurrence {
+                weights: recurrent_weights,
+            }),
+        }
+    }
+
+    pub(crate) fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    /// Number of outputs remembered between propagations (zero for dense
+    /// layers).
+    pub(crate) fn state_size(&self) -> usize {
+        if self.recurrence.is_some() {
+            self.output_size()
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn kind(&self) -> LayerKind {
+        if self.recurrence.is_some() {
+            LayerKind::Recurrent
+        } else {
+            LayerKind::Dense
+        }
+    }
+
+    /// Weights of given neuron.
+    pub(crate) fn row(&self, neuron: usize) -> &[T] {
+        &self.weights[neuron * self.input_size..(neuron + 1) * self.input_size]
+    }
+
+    /// Recurrent weights of given neuron (empty for dense layers).
+    pub(crate) fn recurrent_row(&self, neuron: usize) -> &[T] {
+        match &self.recurrence {
+            Some(recurrence) => {
+                let size = self.output_size();
+
+                &recurrence.weights[neuron * size..(neuron + 1) * size]
+            }
+            None => &[],
+        }
+    }
+
+    /// Bias, weights and recurrent weights of each neuron, in the order
+    /// `Network::weights()` yields them.
+    pub(crate) fn weights(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.output_size())
+            .flat_map(move |neuron| {
+                once(&self.biases[neuron])
+                    .chain(self.row(neuron))
+                    .chain(self.recurrent_row(neuron))
+            })
+            .copied()
+    }
+
+    /// Removes given neuron; in recurrent layers, its contribution to the
+    /// other neurons is folded into their biases, assuming the neuron would
+    /// always output `output`.
+    pub(crate) fn remove_neuron(&mut self, neuron: usize, output: T) {
+        let output_size = self.output_size();
+
+        if let Some(recurrence) = &mut self.recurrence {
+            for (bias, row) in self
+                .biases
+                .iter_mut()
+                .zip(recurrence.weights.chunks_exact(output_size))
+            {
+                *bias += output * row[neuron];
+            }
+
+            remove_column(&mut recurrence.weights, output_size, neuron);
+            recurrence
+                .weights
+                .drain(neuron * (output_size - 1)..(neuron + 1) * (output_size - 1));
+        }
+
+        self.weights
+            .drain(neuron * self.input_size..(neuron + 1) * self.input_size);
+        self.biases.remove(neuron);
+    }
+
+    /// Removes given input, folding its contribution into biases, assuming
+    /// the input would always be `value`.
+    pub(crate) fn remove_input(&mut self, input: usize, value: T) {
+        for (bias, row) in self
+            .biases
+            .iter_mut()
+            .zip(self.weights.chunks_exact(self.input_size))
+        {
+            *bias += value * row[input];
+        }
+
+        remove_column(&mut self.weights, self.input_size, input);
+        self.input_size -= 1;
+    }
+
+    /// Propagates a single sample, overwriting `outputs`; doesn't allocate
+    /// as long as `outputs` has enough capacity.
+    ///
+    /// `state` holds [`Self::state_size()`] outputs from the previous call,
+    /// which recurrent layers read and then overwrite with the new ones.
+    pub(crate) fn propagate_into(&self, inputs: &[T], state: &mut [T], outputs: &mut Vec<T>) {
+        assert_eq!(inputs.len(), self.input_size);
+        assert_eq!(state.len(), self.state_size());
+
+        outputs.clear();
+        outputs.extend_from_slice(&self.biases);
+
+        self.accumulate(inputs, state, outputs);
+        self.activate(outputs);
+
+        state.copy_from_slice(&outputs[..state.len()]);
+    }
+
+    /// Like [`Self::propagate_into()`], but also returns the outputs
+    /// before the activation was applied; recurrent layers start from zero
+    /// state.
+    pub(crate) fn propagate_traced(&self, inputs: &[T]) -> (Vec<T>, Vec<T>) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        let mut pre_activation = self.biases.clone();
+
+        self.accumulate(inputs, &[], &mut pre_activation);
+
+        let mut post_activation = pre_activation.clone();
+
+        self.activate(&mut post_activation);
+
+        (pre_activation, post_activation)
+    }
+
+    /// Propagates `batch` samples laid out one after another in `inputs`,
+    /// writing their outputs one after another into `outputs`.
+    ///
+    /// Samples are independent of each other: in recurrent layers each one
+    /// starts from zero state.
+    pub(crate) fn propagate_batch_into(&self, inputs: &[T], batch: usize, outputs: &mut Vec<T>) {
+        assert_eq!(inputs.len(), batch * self.input_size);
+
+        outputs.clear();
+
+        for _ in 0..batch {
+            outputs.extend_from_slice(&self.biases);
+        }
+
+        for sample in 0..batch {
+            let inputs = &inputs[sample * self.input_size..(sample + 1) * self.input_size];
+            let outputs =
+                &mut outputs[sample * self.output_size()..(sample + 1) * self.output_size()];
+
+            self.accumulate(inputs, &[], outputs);
+        }
+
+        self.activate(outputs);
+    }
+
+    /// Adds weighted inputs (and weighted previous outputs, for recurrent
+    /// layers) to `outputs`, which are expected to contain biases.
+    ///
+    /// Empty `state` stands for all zeros.
+    fn accumulate(&self, inputs: &[T], state: &[T], outputs: &mut [T]) {
+        add_product(&self.weights, inputs, outputs);
+
+        if let Some(recurrence) = &self.recurrence {
+            add_product(&recurrence.weights, state, outputs);
+        }
+    }
+
+    /// Activates outputs of one or more samples laid out one after
+    /// another.
+    fn activate(&self, outputs: &mut [T]) {
+        let size = self.output_size();
+
+        if size == 0 {
+            return;
+        }
+
+        for sample in outputs.chunks_exact_mut(size) {
+            for (range, activation) in segments(self.activation, &self.heads, size) {
+                activation.apply_all(&mut sample[range]);
+            }
+        }
+    }
+}
+
+impl Layer {
+    /// Turns `gradients` of loss with respect to this layer's outputs into
+    /// gradients with respect to its weighted sums, given both (as traced
+    /// by [`Self::propagate_traced()`]).
+    pub(crate) fn backpropagate(&self, pre: &[f32], post: &[f32], gradients: &mut [f32]) {
+        for (range, activation) in segments(self.activation, &self.heads, self.output_size()) {
+            activation.backpropagate(
+                &pre[range.clone()],
+                &post[range.clone()],
+                &mut gradients[range],
+            );
+        }
+    }
+}
+
+/// Removes given column from a row-major matrix.
+fn remove_column<T>(matrix: &mut Vec<T>, columns: usize, column: usize) {
+    let mut idx = 0;
+
+    matrix.retain(|_| {
+        let keep = idx % columns != column;
+        idx += 1;
+        keep
+    });
+}
+
+/// `outputs += matrix * vector`, where `matrix` is row-major with one row
+/// per output.
+fn add_product<T: Scalar>(matrix: &[T], vector: &[T], outputs: &mut [T]) {
+    if vector.is_empty() {
+        return;
+    }
+
+    for (output, row) in outputs.iter_mut().zip(matrix.chunks_exact(vector.len())) {
+        *output += dot(row, vector);
+    }
+}
+
+/// Dot product of two equally-sized slices.
+///
+/// Sums into several independent accumulators, which allows the compiler
+/// to vectorize the loop (a single accumulator forces it to add strictly
+/// one number after another).
+fn dot<T: Scalar>(a: &[T], b: &[T]) -> T {
+    const LANES: usize = 8;
+
+    let mut sums = [T::zero(); LANES];
+    let a_chunks = a.chunks_exact(LANES);
+    let b_chunks = b.chunks_exact(LANES);
+    let tail = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(&a, &b)| a * b)
+        .sum::<T>();
+
+    for (a, b) in a_chunks.zip(b_chunks) {
+        for lane in 0..LANES {
+            sums[lane] += a[lane] * b[lane];
+        }
+    }
+
+    sums.into_iter().sum::<T>() + tail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn random() {
+        // Becuase we always use the same seed, our 'rng' in here will
+        // always return the same set of values
+
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let layer = Layer::<f32>::random(
+            &mut rng,
+            4,
+            &LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+        );
+
+        assert_relative_eq!(layer.biases.as_slice(), [-0.6255188].as_ref());
+        assert_relative_eq!(
+            layer.weights.as_slice(),
+            [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
+        );
+    }
+
+    #[test]
+    fn propagate_into() {
+        let layer = |activation| Layer {
+            input_size: 2,
+            weights: vec![-0.3, 0.8],
+            biases: vec![0.5],
+            activation,
+            heads: Vec::new(),
+            recurrence: None,
+        };
+
+        let mut outputs = Vec::new();
+
+        // Ensure ReLU works:
+        layer(Activation::Relu).propagate_into(&[-10.0, -10.0], &mut [], &mut outputs);
+        assert_relative_eq!(outputs.as_slice(), [0.0].as_ref());
+
+        layer(Activation::Relu).propagate_into(&[0.5, 1.0], &mut [], &mut outputs);
+        assert_relative_eq!(
+            outputs.as_slice(),
+            [(-0.3 * 0.5) + (0.8 * 1.0) + 0.5].as_ref()
+        );
+
+        // ... and that other activations can go negative:
+        layer(Activation::Tanh).propagate_into(&[-10.0, -10.0], &mut [], &mut outputs);
+        assert_relative_eq!(outputs[0], (-0.3f32 * -10.0 + 0.8 * -10.0 + 0.5).tanh());
+    }
+
+    #[test]
+    fn propagate_batch_into() {
+        let layer = Layer {
+            input_size: 2,
+            weights: vec![1.0, 2.0, 3.0, 4.0],
+            biases: vec![0.5, -0.5],
+            activation: Activation::Identity,
+            heads: Vec::new(),
+            recurrence: None,
+        };
+
+        let mut outputs = Vec::new();
+
+        layer.propagate_batch_into(&[1.0, 0.0, 0.0, 1.0, 1.0, 1.0], 3, &mut outputs);
+
+        assert_relative_eq!(outputs.as_slice(), [1.5, 2.5, 2.5, 3.5, 3.5, 6.5].as_ref());
+    }
+
+    fn recurrent_layer() -> Layer {
+        Layer {
+            input_size: 1,
+            weights: vec![1.0, 0.0],
+            biases: vec![0.0, 0.0],
+            activation: Activation::Identity,
+            heads: Vec::new(),
+            recurrence: Some(Recurrence {
+                weights: vec![0.5, 0.0, 1.0, 0.0],
+            }),
+        }
+    }
+
+    #[test]
+    fn recurrent_propagate_into() {
+        let layer = recurrent_layer();
+        let mut state = vec![0.0; 2];
+        let mut outputs = Vec::new();
+
+        // First neuron decays its own previous output by half, second one
+        // just remembers the first neuron's previous output:
+        layer.propagate_into(&[1.0], &mut state, &mut outputs);
+        assert_relative_eq!(outputs.as_slice(), [1.0, 0.0].as_ref());
+
+        layer.propagate_into(&[0.0], &mut state, &mut outputs);
+        assert_relative_eq!(outputs.as_slice(), [0.5, 1.0].as_ref());
+
+        layer.propagate_into(&[0.0], &mut state, &mut outputs);
+        assert_relative_eq!(outputs.as_slice(), [0.25, 0.5].as_ref());
+        assert_relative_eq!(state.as_slice(), [0.25, 0.5].as_ref());
+
+        state.fill(0.0);
+
+        layer.propagate_into(&[0.0], &mut state, &mut outputs);
+        assert_relative_eq!(outputs.as_slice(), [0.0, 0.0].as_ref());
+    }
+
+    #[test]
+    fn recurrent_propagate_batch_into() {
+        let layer = recurrent_layer();
+        let mut outputs = Vec::new();
+
+        layer.propagate_batch_into(&[1.0, 2.0], 2, &mut outputs);
+
+        assert_relative_eq!(outputs.as_slice(), [1.0, 0.0, 2.0, 0.0].as_ref());
+    }
+
+    #[test]
+    fn recurrent_weights() {
+        let layer = recurrent_layer();
+        let actual: Vec<_> = layer.weights().collect();
+
+        // bias, input weight, recurrent weights - for each neuron
+        let expected = [0.0, 1.0, 0.5, 0.0, 0.0, 0.0, 1.0, 0.0];
+
+        assert_relative_eq!(actual.as_slice(), expected.as_ref());
+    }
+
+    #[test]
+    fn remove_neuron() {
+        let mut layer = recurrent_layer();
+
+        // Neuron 1 sees neuron 0's previous output, assumed to be 2
+        layer.remove_neuron(0, 2.0);
+
+        let actual: Vec<_> = layer.weights().collect();
+
+        assert_relative_eq!(actual.as_slice(), [2.0, 0.0, 0.0].as_ref());
+        assert_eq!(layer.state_size(), 1);
+    }
+
+    #[test]
+    fn remove_input() {
+        let mut layer = Layer {
+            input_size: 2,
+            weights: vec![1.0, 2.0, 3.0, 4.0],
+            biases: vec![0.5, -0.5],
+            activation: Activation::Identity,
+            heads: Vec::new(),
+            recurrence: None,
+        };
+
+        layer.remove_input(1, 10.0);
+
+        assert_eq!(layer.input_size, 1);
+        assert_relative_eq!(layer.weights.as_slice(), [1.0, 3.0].as_ref());
+        assert_relative_eq!(layer.biases.as_slice(), [20.5, 39.5].as_ref());
+    }
+}
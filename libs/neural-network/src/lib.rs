@@ -1,185 +1,485 @@
+mod activation;
+mod distance;
+mod error;
+mod head;
+mod initializer;
+mod layer;
+mod neat;
+mod persistence;
+mod prune;
+mod quantize;
+mod render;
+mod scalar;
+mod trace;
+mod train;
+
+use self::layer::*;
+pub use self::{
+    activation::*, distance::*, error::*, head::*, initializer::*, neat::*, persistence::*,
+    prune::*, quantize::*, scalar::*, trace::*, train::*,
+};
 use rand::{Rng, RngCore};
-use std::iter::once;
-
-#[derive(Debug)]
-pub struct Network {
-    layers: Vec<Layer>,
+use serde::{Deserialize, Serialize};
+use std::{iter::once, mem};
+
+/// Feed-forward neural network (optionally with recurrent layers),
+/// computing with `f32` unless told otherwise - see [`Scalar`].
+#[derive(Clone, Debug)]
+pub struct Network<T = f32> {
+    layers: Vec<Layer<T>>,
 }
 
 impl Network {
     pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
-        assert!(layers.len() > 1);
+        Self::random_scalar(rng, layers)
+    }
 
-        let layers = layers
-            .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
-            .collect();
+    pub fn try_random(
+        rng: &mut dyn RngCore,
+        layers: &[LayerTopology],
+    ) -> Result<Self, NetworkError> {
+        Self::try_random_scalar(rng, layers)
+    }
 
-        Self { layers }
+    pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
+        Self::from_scalar_weights(layers, weights)
     }
 
-    pub fn propogate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.layers
-            .iter()
-            .fold(inputs, |inputs, layer| layer.propogate(inputs))
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Self, NetworkError> {
+        Self::try_from_scalar_weights(layers, weights)
     }
+}
 
-    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
-        self.layers
-            .iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .copied()
+impl<T: Scalar> Network<T> {
+    /// Like [`Network::random()`], but for any [`Scalar`] - e.g.
+    /// `Network::<f64>::random_scalar()`.
+    ///
+    /// Given seed yields the same weights no matter the scalar (up to its
+    /// precision).
+    pub fn random_scalar(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
+        Self::try_random_scalar(rng, layers).unwrap_or_else(|err| panic!("{err}"))
     }
 
-    pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
-        assert!(layers.len() > 1);
+    pub fn try_random_scalar(
+        rng: &mut dyn RngCore,
+        layers: &[LayerTopology],
+    ) -> Result<Self, NetworkError> {
+        if layers.len() < 2 {
+            return Err(NetworkError::EmptyTopology);
+        }
 
-        let mut weights = weights.into_iter();
+        check_heads(layers)?;
+        weight_count(layers).ok_or(NetworkError::TopologyTooLarge)?;
+
+        // Input layer's initializer is never used
+        if let Some(layer) = (1..layers.len()).find(|&idx| !layers[idx].initializer.is_valid()) {
+            return Err(NetworkError::InvalidInitializer { layer });
+        }
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
+            .map(|layers| Layer::random(rng, layers[0].neurons, &layers[1]))
             .collect();
 
-        if weights.next().is_some() {
-            panic!("got too many weights");
-        }
+        Ok(Self { layers })
+    }
 
-        Self { layers }
+    /// Recurrent layers start from zero state on each call - to carry it
+    /// over, see [`Self::propagate_into()`].
+    pub fn propogate(&self, inputs: Vec<T>) -> Vec<T> {
+        self.try_propogate(inputs)
+            .unwrap_or_else(|err| panic!("{err}"))
     }
-}
 
-#[derive(Debug)]
-struct Layer {
-    neurons: Vec<Neuron>,
-}
+    pub fn try_propogate(&self, inputs: Vec<T>) -> Result<Vec<T>, NetworkError> {
+        self.check_input_len(inputs.len())?;
 
-impl Layer {
-    fn random(rng: &mut dyn RngCore, input_size: usize, output_size: usize) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::random(rng, input_size))
-            .collect();
+        Ok(self
+            .propagate_into(&inputs, &mut Scratch::default())
+            .to_vec())
+    }
+
+    /// Like [`Self::propogate()`], but reuses `scratch`'s buffers instead
+    /// of allocating new ones for each layer - after the first call with
+    /// given scratch, propagation doesn't allocate at all.
+    ///
+    /// Returned slice borrows from `scratch` and stays valid until it's
+    /// used again.
+    ///
+    /// Recurrent layers keep their state in `scratch`, carrying it over to
+    /// the next call with the same scratch - see [`LayerKind::Recurrent`].
+    pub fn propagate_into<'a>(&self, inputs: &[T], scratch: &'a mut Scratch<T>) -> &'a [T] {
+        if let Err(err) = self.check_input_len(inputs.len()) {
+            panic!("{err}");
+        }
+
+        let Scratch {
+            front, back, state, ..
+        } = scratch;
+
+        self.fit_state(state);
+
+        front.clear();
+        front.extend_from_slice(inputs);
 
-        Self { neurons }
+        for (layer, state) in self.layers.iter().zip(state.iter_mut()) {
+            layer.propagate_into(front, state, back);
+            mem::swap(front, back);
+        }
+
+        front
     }
 
-    fn propogate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
-            .iter()
-            .map(|neuron| neuron.propogate(&inputs))
-            .collect()
+    /// Makes sure `state` has room for each layer's state, zeroing it if
+    /// it was shaped for a different network.
+    fn fit_state(&self, state: &mut Vec<Vec<T>>) {
+        let fits = state.len() == self.layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(state.iter())
+                .all(|(layer, state)| layer.state_size() == state.len());
+
+        if !fits {
+            state.clear();
+
+            state.extend(
+                self.layers
+                    .iter()
+                    .map(|layer| vec![T::zero(); layer.state_size()]),
+            );
+        }
     }
 
-    fn from_weights(
-        input_size: usize,
-        output_size: usize,
-        weights: &mut dyn Iterator<Item = f32>,
-    ) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
-            .collect();
+    /// Propagates many samples at once.
+    ///
+    /// `inputs` holds the samples one after another (so its length must
+    /// be a multiple of the input layer's size) and the returned slice
+    /// holds their outputs in the same order.
+    ///
+    /// Samples are independent of each other, so this panics for networks
+    /// with recurrent layers - their state would have nowhere to go.
+    pub fn propagate_batch<'a>(&self, inputs: &[T], scratch: &'a mut Scratch<T>) -> &'a [T] {
+        if let Err(err) = self.check_feed_forward() {
+            panic!("{err}");
+        }
 
-        Self { neurons }
+        self.propagate_samples(inputs, scratch)
     }
-}
 
-#[derive(Debug)]
-struct Neuron {
-    bias: f32,
-    weights: Vec<f32>,
-}
+    /// Like [`Self::propagate_batch()`], but lets recurrent layers through,
+    /// starting them from zero state for each sample.
+    pub(crate) fn propagate_samples<'a>(
+        &self,
+        inputs: &[T],
+        scratch: &'a mut Scratch<T>,
+    ) -> &'a [T] {
+        let input_size = self.layers[0].input_size;
+
+        assert!(input_size > 0, "cannot batch inputs of a zero-sized layer");
+        assert_eq!(
+            inputs.len() % input_size,
+            0,
+            "got {} inputs, which is not a multiple of input size {}",
+            inputs.len(),
+            input_size
+        );
 
-impl Neuron {
-    fn random(rng: &mut dyn RngCore, input_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
+        let batch = inputs.len() / input_size;
+        let Scratch { front, back, .. } = scratch;
+
+        front.clear();
+        front.extend_from_slice(inputs);
+
+        for layer in &self.layers {
+            layer.propagate_batch_into(front, batch, back);
+            mem::swap(front, back);
+        }
 
-        let weights = (0..input_size).map(|_| rng.gen_range(-1.0..=1.0)).collect();
+        front
+    }
 
-        Self { bias, weights }
+    /// Propagates one sample through each of `networks` - e.g. each bird's
+    /// vision through its own brain - in a single call.
+    ///
+    /// `inputs` holds the samples one after another, in the same order as
+    /// `networks`, and the returned slice holds their outputs likewise.
+    /// Networks can differ in their hidden layers, but must agree on the
+    /// sizes of their input and output layers; just like with
+    /// [`Self::propagate_batch()`], they can't be recurrent.
+    pub fn propagate_many<'a, 'n, I>(
+        networks: I,
+        inputs: &[T],
+        scratch: &'a mut Scratch<T>,
+    ) -> &'a [T]
+    where
+        I: IntoIterator<Item = &'n Self>,
+        I::IntoIter: Clone,
+        T: 'n,
+    {
+        Self::try_propagate_many(networks, inputs, scratch).unwrap_or_else(|err| panic!("{err}"))
     }
 
-    fn propogate(&self, inputs: &[f32]) -> f32 {
-        assert_eq!(inputs.len(), self.weights.len());
+    pub fn try_propagate_many<'a, 'n, I>(
+        networks: I,
+        inputs: &[T],
+        scratch: &'a mut Scratch<T>,
+    ) -> Result<&'a [T], NetworkError>
+    where
+        I: IntoIterator<Item = &'n Self>,
+        I::IntoIter: Clone,
+        T: 'n,
+    {
+        let networks = networks.into_iter();
+
+        let (input_size, output_size) = networks.clone().next().map_or((0, 0), |network| {
+            (network.layers[0].input_size, network.output_size())
+        });
+
+        let mut count = 0;
+
+        for network in networks.clone() {
+            if network.layers[0].input_size != input_size || network.output_size() != output_size {
+                return Err(NetworkError::TopologyMismatch);
+            }
+
+            network.check_feed_forward()?;
+            count += 1;
+        }
 
-        let output = inputs
-            .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
+        if inputs.len() != count * input_size {
+            return Err(NetworkError::InputLengthMismatch {
+                expected: count * input_size,
+                actual: inputs.len(),
+            });
+        }
+
+        let Scratch {
+            front,
+            back,
+            outputs,
+            ..
+        } = scratch;
+
+        outputs.clear();
+
+        for (idx, network) in networks.enumerate() {
+            front.clear();
+            front.extend_from_slice(&inputs[idx * input_size..(idx + 1) * input_size]);
+
+            for layer in &network.layers {
+                layer.propagate_into(front, &mut [], back);
+                mem::swap(front, back);
+            }
+
+            outputs.extend_from_slice(front);
+        }
+
+        Ok(outputs)
+    }
+
+    pub fn weights(&self) -> impl Iterator<Item = T> + '_ {
+        self.layers.iter().flat_map(|layer| layer.weights())
+    }
+
+    pub(crate) fn output_size(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.output_size())
+    }
 
-        (self.bias + output).max(0.0)
+    fn check_feed_forward(&self) -> Result<(), NetworkError> {
+        if self.layers.iter().any(|layer| layer.recurrence.is_some()) {
+            Err(NetworkError::RecurrentBatch)
+        } else {
+            Ok(())
+        }
     }
 
-    fn from_weights(input_size: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = weights.next().expect("got not enought weights");
+    fn check_input_len(&self, actual: usize) -> Result<(), NetworkError> {
+        let expected = self.layers[0].input_size;
+
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(NetworkError::InputLengthMismatch { expected, actual })
+        }
+    }
+
+    /// Like [`Network::from_weights()`], but for any [`Scalar`].
+    pub fn from_scalar_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = T>,
+    ) -> Self {
+        Self::try_from_scalar_weights(layers, weights).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_scalar_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = T>,
+    ) -> Result<Self, NetworkError> {
+        if layers.len() < 2 {
+            return Err(NetworkError::EmptyTopology);
+        }
+
+        check_heads(layers)?;
 
-        let weights = (0..input_size)
-            .map(|_| weights.next().expect("got not enought weights"))
+        let weights: Vec<_> = weights.into_iter().collect();
+        let expected = weight_count(layers).ok_or(NetworkError::TopologyTooLarge)?;
+
+        if weights.len() < expected {
+            return Err(NetworkError::TooFewWeights {
+                expected,
+                actual: weights.len(),
+            });
+        }
+
+        if weights.len() > expected {
+            return Err(NetworkError::TooManyWeights {
+                expected,
+                actual: weights.len(),
+            });
+        }
+
+        let mut weights = weights.into_iter();
+
+        let layers = layers
+            .windows(2)
+            .map(|layers| Layer::from_weights(layers[0].neurons, &layers[1], &mut weights))
             .collect();
 
-        Self { bias, weights }
+        Ok(Self { layers })
     }
 }
 
-#[derive(Debug)]
+/// Number of weights (biases included) a network of given topology has;
+/// `None` if it doesn't fit in `usize` (e.g. for a corrupted file).
+pub(crate) fn weight_count(layers: &[LayerTopology]) -> Option<usize> {
+    layers.windows(2).try_fold(0usize, |count, layers| {
+        let inputs = match layers[1].kind {
+            LayerKind::Dense => Some(layers[0].neurons),
+            LayerKind::Recurrent => layers[0].neurons.checked_add(layers[1].neurons),
+        }?;
+
+        inputs
+            .checked_add(1)?
+            .checked_mul(layers[1].neurons)?
+            .checked_add(count)
+    })
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct LayerTopology {
     pub neurons: usize,
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use approx::assert_relative_eq;
-    use rand::SeedableRng;
-    use rand_chacha::ChaCha8Rng;
+    /// Activation applied to this layer's neurons; ignored for the
+    /// input layer, which has no neurons of its own.
+    pub activation: Activation,
 
-    #[test]
-    fn random() {
-        // Becuase we always use the same seed, our 'rng' in here will
-        // always return the same set of values
+    /// Ignored for the input layer, too.
+    #[serde(default)]
+    pub kind: LayerKind,
 
-        let mut rng = ChaCha8Rng::from_seed(Default::default());
-        let neuron = Neuron::random(&mut rng, 4);
+    /// Used only by [`Network::random()`], so it's not saved alongside
+    /// the network; ignored for the input layer.
+    #[serde(skip)]
+    pub initializer: Initializer,
 
-        assert_relative_eq!(neuron.bias, -0.6255188);
-        assert_relative_eq!(
-            neuron.weights.as_slice(),
-            [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
-        );
-    }
+    /// Splits the output layer into named heads, each with its own
+    /// activation (overriding `activation`); must be empty for other
+    /// layers.
+    #[serde(default)]
+    pub heads: Vec<OutputHead>,
+}
 
-    #[test]
-    fn propogate() {
-        let neuron = Neuron {
-            bias: 0.5,
-            weights: vec![-0.3, 0.8],
-        };
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerKind {
+    /// Output depends only on the current inputs
+    #[default]
+    Dense,
+
+    /// Elman-style layer: besides the current inputs, each neuron also
+    /// sees all of the layer's outputs from the previous propagation.
+    ///
+    /// This state lives in the [`Scratch`] passed to
+    /// [`Network::propagate_into()`] and persists between calls with the
+    /// same scratch until [`Scratch::reset_state()`] - so each network that
+    /// should remember anything needs its own scratch. Other ways of
+    /// propagating, such as [`Network::propogate()`] or
+    /// [`Network::propagate_traced()`], start from zero state, while
+    /// [`Network::propagate_batch()`] refuses recurrent networks.
+    Recurrent,
+}
 
-        // Ensure '.max()' (our ReLU) works:
-        assert_relative_eq!(neuron.propogate(&[-10.0, -10.0]), 0.0,);
+/// Reusable buffers for [`Network::propagate_into()`],
+/// [`Network::propagate_batch()`], [`Network::propagate_many()`] and
+/// [`QuantizedNetwork::propagate_into()`].
+///
+/// A single scratch can be shared by feed-forward networks of different
+/// shapes; it grows to fit the largest one it's been used with. Recurrent
+/// networks keep their state here, so each one needs its own scratch.
+#[derive(Clone, Debug, Default)]
+pub struct Scratch<T = f32> {
+    front: Vec<T>,
+    back: Vec<T>,
+
+    /// Layer's inputs, as quantized by [`QuantizedNetwork`]
+    quantized: Vec<i8>,
+
+    /// Outputs of all networks given to [`Network::propagate_many()`]
+    outputs: Vec<T>,
+
+    /// Each layer's outputs from the previous propagation (empty for
+    /// dense layers) - see [`LayerKind::Recurrent`]
+    state: Vec<Vec<T>>,
+}
 
-        assert_relative_eq!(
-            neuron.propogate(&[0.5, 1.0]),
-            (-0.3 * 0.5) + (0.8 * 1.0) + 0.5,
-        );
+impl Scratch {
+    /// Creates scratch for `f32` networks; for other scalars, use
+    /// [`Scratch::default()`].
+    pub fn new() -> Self {
+        Self::default()
     }
+}
+
+impl<T: Scalar> Scratch<T> {
+    /// Forgets everything recurrent layers remembered, as if the network
+    /// has just been created.
+    pub fn reset_state(&mut self) {
+        for state in &mut self.state {
+            state.fill(T::zero());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn weights() {
         let network = Network {
             layers: vec![
                 Layer {
-                    neurons: vec![Neuron {
-                        bias: 0.1,
-                        weights: vec![0.2, 0.3, 0.4],
-                    }],
+                    input_size: 3,
+                    weights: vec![0.2, 0.3, 0.4],
+                    biases: vec![0.1],
+                    activation: Activation::Relu,
+                    heads: Vec::new(),
+                    recurrence: None,
                 },
                 Layer {
-                    neurons: vec![Neuron {
-                        bias: 0.5,
-                        weights: vec![0.6, 0.7, 0.8],
-                    }],
+                    input_size: 3,
+                    weights: vec![0.6, 0.7, 0.8],
+                    biases: vec![0.5],
+                    activation: Activation::Relu,
+                    heads: Vec::new(),
+                    recurrence: None,
                 },
             ],
         };
@@ -192,7 +492,16 @@ mod tests {
 
     #[test]
     fn from_weights() {
-        let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+        let layers = &[
+            LayerTopology {
+                neurons: 3,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+        ];
 
         let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
         let network = Network::from_weights(layers, weights.clone());
@@ -200,4 +509,349 @@ mod tests {
 
         assert_relative_eq!(actual.as_slice(), weights.as_slice());
     }
+
+    #[test]
+    fn try_from_weights() {
+        let layers = &[
+            LayerTopology {
+                neurons: 3,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            Network::try_from_weights(layers, vec![0.1; 7]).unwrap_err(),
+            NetworkError::TooFewWeights {
+                expected: 8,
+                actual: 7
+            }
+        );
+
+        assert_eq!(
+            Network::try_from_weights(layers, vec![0.1; 9]).unwrap_err(),
+            NetworkError::TooManyWeights {
+                expected: 8,
+                actual: 9
+            }
+        );
+
+        assert_eq!(
+            Network::try_from_weights(&layers[..1], vec![]).unwrap_err(),
+            NetworkError::EmptyTopology
+        );
+
+        assert!(Network::try_from_weights(layers, vec![0.1; 8]).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "got too many weights")]
+    fn from_weights_panics_on_too_many_weights() {
+        let layers = &[
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+        ];
+
+        Network::from_weights(layers, vec![0.1; 3]);
+    }
+
+    #[test]
+    fn try_propogate() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 3,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        assert_eq!(
+            network.try_propogate(vec![1.0, 2.0]).unwrap_err(),
+            NetworkError::InputLengthMismatch {
+                expected: 3,
+                actual: 2
+            }
+        );
+
+        assert_eq!(network.try_propogate(vec![1.0, 2.0, 3.0]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn try_random() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        assert_eq!(
+            Network::try_random(&mut rng, &[]).unwrap_err(),
+            NetworkError::EmptyTopology
+        );
+    }
+
+    #[test]
+    fn from_weights_honours_activation() {
+        let layers = |activation| {
+            [
+                LayerTopology {
+                    neurons: 1,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    activation,
+                    ..Default::default()
+                },
+            ]
+        };
+
+        let weights = [0.0, -1.0];
+
+        let relu = Network::from_weights(&layers(Activation::Relu), weights);
+        let identity = Network::from_weights(&layers(Activation::Identity), weights);
+
+        assert_relative_eq!(relu.propogate(vec![2.0])[0], 0.0);
+        assert_relative_eq!(identity.propogate(vec![2.0])[0], -2.0);
+    }
+
+    #[test]
+    fn random_honours_activation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let layers = [
+            LayerTopology {
+                neurons: 4,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 8,
+                activation: Activation::Sigmoid,
+                ..Default::default()
+            },
+        ];
+
+        let network = Network::random(&mut rng, &layers);
+        let output = network.propogate(vec![-5.0, 3.0, 0.0, 10.0]);
+
+        assert_eq!(output.len(), 8);
+        assert!(output.iter().all(|&y| y > 0.0 && y < 1.0));
+    }
+
+    fn network(rng: &mut dyn RngCore) -> Network {
+        Network::random(
+            rng,
+            &[
+                LayerTopology {
+                    neurons: 3,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 5,
+                    activation: Activation::Relu,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 2,
+                    activation: Activation::Tanh,
+                    ..Default::default()
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn propagate_into() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = network(&mut rng);
+        let mut scratch = Scratch::new();
+
+        for inputs in [[0.1, 0.2, 0.3], [-1.0, 0.5, 2.0], [0.0, 0.0, 0.0]] {
+            let expected = network.propogate(inputs.to_vec());
+            let actual = network.propagate_into(&inputs, &mut scratch);
+
+            assert_relative_eq!(actual, expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn propagate_batch() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = network(&mut rng);
+        let mut scratch = Scratch::new();
+
+        let inputs = [[0.1, 0.2, 0.3], [-1.0, 0.5, 2.0], [0.0, 0.0, 0.0]];
+
+        let expected: Vec<_> = inputs
+            .iter()
+            .flat_map(|inputs| network.propogate(inputs.to_vec()))
+            .collect();
+
+        let actual = network.propagate_batch(&inputs.concat(), &mut scratch);
+
+        assert_relative_eq!(actual, expected.as_slice());
+    }
+
+    #[test]
+    fn propagate_many() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let networks: Vec<_> = (0..3).map(|_| network(&mut rng)).collect();
+        let mut scratch = Scratch::new();
+
+        let inputs = [[0.1, 0.2, 0.3], [-1.0, 0.5, 2.0], [0.0, 0.0, 0.0]];
+
+        let expected: Vec<_> = networks
+            .iter()
+            .zip(&inputs)
+            .flat_map(|(network, inputs)| network.propogate(inputs.to_vec()))
+            .collect();
+
+        let actual = Network::propagate_many(&networks, &inputs.concat(), &mut scratch);
+
+        assert_relative_eq!(actual, expected.as_slice());
+    }
+
+    #[test]
+    fn try_propagate_many() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = network(&mut rng);
+        let mut scratch = Scratch::new();
+
+        assert_eq!(
+            Network::try_propagate_many([&network, &network], &[1.0; 3], &mut scratch).unwrap_err(),
+            NetworkError::InputLengthMismatch {
+                expected: 6,
+                actual: 3
+            }
+        );
+
+        let other = Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 3,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        assert_eq!(
+            Network::try_propagate_many([&network, &other], &[1.0; 6], &mut scratch).unwrap_err(),
+            NetworkError::TopologyMismatch
+        );
+
+        assert_eq!(
+            Network::try_propagate_many([], &[], &mut scratch).unwrap(),
+            &[] as &[f32]
+        );
+    }
+
+    #[test]
+    fn recurrent() {
+        let layers = [
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Identity,
+                kind: LayerKind::Recurrent,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ];
+
+        // Hidden layer: for each neuron bias, input weight and two
+        // recurrent weights; the first neuron passes the input through,
+        // the second one remembers what the first one saw previously.
+        //
+        // Output layer: returns the second hidden neuron.
+        let weights = vec![
+            0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0, //
+            0.0, 0.0, 1.0,
+        ];
+
+        let network = Network::from_weights(&layers, weights.clone());
+
+        assert_eq!(weight_count(&layers), Some(weights.len()));
+        assert_eq!(network.weights().collect::<Vec<_>>(), weights);
+
+        let mut scratch = Scratch::new();
+
+        assert_relative_eq!(network.propagate_into(&[3.0], &mut scratch)[0], 0.0);
+        assert_relative_eq!(network.propagate_into(&[5.0], &mut scratch)[0], 3.0);
+        assert_relative_eq!(network.propagate_into(&[7.0], &mut scratch)[0], 5.0);
+
+        // Each scratch carries its own state
+        let mut other = Scratch::new();
+
+        assert_relative_eq!(network.propagate_into(&[2.0], &mut other)[0], 0.0);
+        assert_relative_eq!(network.propagate_into(&[8.0], &mut scratch)[0], 7.0);
+
+        scratch.reset_state();
+
+        assert_relative_eq!(network.propagate_into(&[9.0], &mut scratch)[0], 0.0);
+
+        // `propogate()` always starts from scratch
+        assert_relative_eq!(network.propogate(vec![3.0])[0], 0.0);
+        assert_relative_eq!(network.propogate(vec![5.0])[0], 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "networks with recurrent layers cannot be batched")]
+    fn propagate_batch_rejects_recurrent() {
+        let layers = [
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                kind: LayerKind::Recurrent,
+                ..Default::default()
+            },
+        ];
+
+        let network = Network::from_weights(&layers, [0.0, 1.0, 1.0]);
+
+        assert_eq!(
+            Network::try_propagate_many([&network], &[1.0], &mut Scratch::new()).unwrap_err(),
+            NetworkError::RecurrentBatch
+        );
+
+        network.propagate_batch(&[1.0, 2.0], &mut Scratch::new());
+    }
+
+    #[test]
+    fn is_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        // State of recurrent layers lives in `Scratch`, so shared networks
+        // can be propagated from many threads at once
+        assert_send_sync::<Network>();
+        assert_send_sync::<Network<f64>>();
+    }
 }
@@ -0,0 +1,781 @@
+//! NEAT-style networks (NeuroEvolution of Augmenting Topologies), whose
+//! shape - not just weights - is subject to evolution.
+//!
+//! A [`Genome`] lists nodes and the connections between them; each
+//! connection carries an innovation number handed out by an
+//! [`InnovationTracker`], which lets genomes of different shapes be
+//! aligned gene-by-gene during crossover.
+
+use crate::*;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Input,
+    Hidden,
+    Output,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+
+    /// Ignored for input nodes
+    pub bias: f32,
+
+    /// Ignored for input nodes
+    pub activation: Activation,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionGene {
+    /// Historical marking: connections between the same pair of nodes
+    /// get the same innovation number in every genome
+    pub innovation: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+    pub enabled: bool,
+}
+
+/// Hands out innovation numbers and node ids, so that the same
+/// structural mutation happening in different genomes yields identical
+/// genes.
+///
+/// A single tracker should be shared by the whole population.
+#[derive(Clone, Debug, Default)]
+pub struct InnovationTracker {
+    connections: HashMap<(usize, usize), usize>,
+    splits: HashMap<usize, usize>,
+    next_innovation: usize,
+    next_node: usize,
+}
+
+impl InnovationTracker {
+    /// Creates tracker for genomes with given number of inputs and
+    /// outputs - node ids below `inputs + outputs` are reserved for them.
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        Self {
+            next_node: inputs + outputs,
+            ..Default::default()
+        }
+    }
+
+    /// Innovation number of connection `from` -> `to`.
+    pub fn connection(&mut self, from: usize, to: usize) -> usize {
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            self.next_innovation += 1;
+            self.next_innovation - 1
+        })
+    }
+
+    /// Id of the node that gets inserted when connection with given
+    /// innovation number is split.
+    pub fn split(&mut self, innovation: usize) -> usize {
+        *self.splits.entry(innovation).or_insert_with(|| {
+            self.next_node += 1;
+            self.next_node - 1
+        })
+    }
+
+    fn fresh_node(&mut self) -> usize {
+        self.next_node += 1;
+        self.next_node - 1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    /// Sorted by id
+    nodes: Vec<NodeGene>,
+
+    /// Sorted by innovation number
+    connections: Vec<ConnectionGene>,
+
+    /// Activation given to nodes created by [`Self::mutate_add_node()`]
+    hidden_activation: Activation,
+}
+
+impl Genome {
+    /// Creates the smallest possible genome: every input connected to
+    /// every output, with random weights and biases.
+    pub fn minimal(
+        rng: &mut dyn RngCore,
+        tracker: &mut InnovationTracker,
+        inputs: usize,
+        outputs: usize,
+        output_activation: Activation,
+        hidden_activation: Activation,
+    ) -> Self {
+        let nodes = (0..inputs)
+            .map(|id| NodeGene {
+                id,
+                kind: NodeKind::Input,
+                bias: 0.0,
+                activation: Activation::Identity,
+            })
+            .chain((inputs..inputs + outputs).map(|id| NodeGene {
+                id,
+                kind: NodeKind::Output,
+                bias: rng.gen_range(-1.0..=1.0),
+                activation: output_activation,
+            }))
+            .collect();
+
+        let mut connections = Vec::with_capacity(inputs * outputs);
+
+        for from in 0..inputs {
+            for to in inputs..inputs + outputs {
+                connections.push(ConnectionGene {
+                    innovation: tracker.connection(from, to),
+                    from,
+                    to,
+                    weight: rng.gen_range(-1.0..=1.0),
+                    enabled: true,
+                });
+            }
+        }
+
+        connections.sort_by_key(|connection| connection.innovation);
+
+        Self {
+            nodes,
+            connections,
+            hidden_activation,
+        }
+    }
+
+    pub fn nodes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    /// Connects two so-far unconnected nodes, picked at random, with a
+    /// random weight.
+    ///
+    /// Only connections that keep the network feed-forward are
+    /// considered; returns `false` if there are none left.
+    pub fn mutate_add_connection(
+        &mut self,
+        rng: &mut dyn RngCore,
+        tracker: &mut InnovationTracker,
+    ) -> bool {
+        let candidates: Vec<_> = self
+            .nodes
+            .iter()
+            .filter(|from| from.kind != NodeKind::Output)
+            .flat_map(|from| {
+                self.nodes
+                    .iter()
+                    .filter(|to| to.kind != NodeKind::Input && to.id != from.id)
+                    .map(move |to| (from.id, to.id))
+            })
+            .filter(|&(from, to)| !self.is_connected(from, to) && !self.reaches(to, from))
+            .collect();
+
+        let Some(&(from, to)) = candidates.choose(rng) else {
+            return false;
+        };
+
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection(from, to),
+            from,
+            to,
+            weight: rng.gen_range(-1.0..=1.0),
+            enabled: true,
+        });
+
+        true
+    }
+
+    /// Splits a random enabled connection `a -> b` into `a -> new -> b`.
+    ///
+    /// The old connection gets disabled; `a -> new` gets weight of 1.0 and
+    /// `new -> b` inherits the old weight, so the network initially
+    /// behaves (almost) the same. Returns `false` if there's no enabled
+    /// connection to split.
+    pub fn mutate_add_node(
+        &mut self,
+        rng: &mut dyn RngCore,
+        tracker: &mut InnovationTracker,
+    ) -> bool {
+        let enabled: Vec<_> = (0..self.connections.len())
+            .filter(|&idx| self.connections[idx].enabled)
+            .collect();
+
+        let Some(&idx) = enabled.choose(rng) else {
+            return false;
+        };
+
+        let old = self.connections[idx].clone();
+
+        self.connections[idx].enabled = false;
+
+        let mut node = tracker.split(old.innovation);
+
+        // Genome might already contain this node if it's split this
+        // connection before, and then got it re-enabled via crossover
+        if self.node(node).is_some() {
+            node = tracker.fresh_node();
+        }
+
+        self.insert_node(NodeGene {
+            id: node,
+            kind: NodeKind::Hidden,
+            bias: 0.0,
+            activation: self.hidden_activation,
+        });
+
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection(old.from, node),
+            from: old.from,
+            to: node,
+            weight: 1.0,
+            enabled: true,
+        });
+
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection(node, old.to),
+            from: node,
+            to: old.to,
+            weight: old.weight,
+            enabled: true,
+        });
+
+        true
+    }
+
+    /// Nudges weights and biases, in the spirit of `GaussianMutation`:
+    /// each one is touched with probability `chance` and changed by at
+    /// most `coeff`.
+    pub fn mutate_weights(&mut self, rng: &mut dyn RngCore, chance: f32, coeff: f32) {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(coeff >= 0.0);
+
+        let biases = self
+            .nodes
+            .iter_mut()
+            .filter(|node| node.kind != NodeKind::Input)
+            .map(|node| &mut node.bias);
+
+        let weights = self
+            .connections
+            .iter_mut()
+            .map(|connection| &mut connection.weight);
+
+        for value in biases.chain(weights) {
+            if rng.gen_bool(chance as f64) {
+                *value += rng.gen_range(-coeff..=coeff);
+            }
+        }
+    }
+
+    /// Creates child of two genomes by aligning their connections by
+    /// innovation number.
+    ///
+    /// Matching genes are inherited from a random parent; disjoint and
+    /// excess genes only from `fitter` - so the child has exactly the
+    /// fitter parent's structure. A gene disabled in either parent stays
+    /// disabled with 75% probability.
+    pub fn crossover(rng: &mut dyn RngCore, fitter: &Genome, other: &Genome) -> Genome {
+        let other_connections: HashMap<_, _> = other
+            .connections
+            .iter()
+            .map(|connection| (connection.innovation, connection))
+            .collect();
+
+        let connections = fitter
+            .connections
+            .iter()
+            .map(
+                |connection| match other_connections.get(&connection.innovation) {
+                    Some(&matching) => {
+                        let mut child = if rng.gen_bool(0.5) {
+                            connection.clone()
+                        } else {
+                            matching.clone()
+                        };
+
+                        child.enabled = if connection.enabled && matching.enabled {
+                            true
+                        } else {
+                            !rng.gen_bool(0.75)
+                        };
+
+                        child
+                    }
+                    None => connection.clone(),
+                },
+            )
+            .collect();
+
+        let nodes = fitter
+            .nodes
+            .iter()
+            .map(|node| match other.node(node.id) {
+                Some(matching) if rng.gen_bool(0.5) => NodeGene {
+                    bias: matching.bias,
+                    ..node.clone()
+                },
+                _ => node.clone(),
+            })
+            .collect();
+
+        Genome {
+            nodes,
+            connections,
+            hidden_activation: fitter.hidden_activation,
+        }
+    }
+
+    fn node(&self, id: usize) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|idx| &self.nodes[idx])
+    }
+
+    fn insert_node(&mut self, node: NodeGene) {
+        let idx = self.nodes.partition_point(|other| other.id < node.id);
+
+        self.nodes.insert(idx, node);
+    }
+
+    fn insert_connection(&mut self, connection: ConnectionGene) {
+        let idx = self
+            .connections
+            .partition_point(|other| other.innovation < connection.innovation);
+
+        self.connections.insert(idx, connection);
+    }
+
+    fn is_connected(&self, from: usize, to: usize) -> bool {
+        self.connections
+            .iter()
+            .any(|connection| connection.from == from && connection.to == to)
+    }
+
+    /// Whether there's a path `from` -> ... -> `to`, following both
+    /// enabled and disabled connections (as the latter can get re-enabled
+    /// by crossover).
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut visited = vec![from];
+        let mut pending = vec![from];
+
+        while let Some(node) = pending.pop() {
+            if node == to {
+                return true;
+            }
+
+            for connection in &self.connections {
+                if connection.from == node && !visited.contains(&connection.to) {
+                    visited.push(connection.to);
+                    pending.push(connection.to);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Network built from a [`Genome`], ready to be propagated.
+#[derive(Clone, Debug)]
+pub struct NeatNetwork {
+    inputs: usize,
+
+    /// Non-input nodes, in the order they have to be evaluated
+    nodes: Vec<NeatNode>,
+
+    /// Where to look for outputs in the values computed during propagation
+    outputs: Vec<usize>,
+
+    /// How many values propagation computes (one per node)
+    values: usize,
+}
+
+#[derive(Clone, Debug)]
+struct NeatNode {
+    value: usize,
+    bias: f32,
+    activation: Activation,
+
+    /// `(value, weight)` for each enabled incoming connection
+    incoming: Vec<(usize, f32)>,
+}
+
+impl NeatNetwork {
+    pub fn from_genome(genome: &Genome) -> Self {
+        Self::try_from_genome(genome).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`Self::from_genome()`], but returns an error for genomes that
+    /// can't be turned into a network - which only loaded ones can be.
+    pub fn try_from_genome(genome: &Genome) -> Result<Self, NetworkError> {
+        if genome
+            .nodes
+            .windows(2)
+            .any(|nodes| nodes[0].id >= nodes[1].id)
+        {
+            return Err(NetworkError::UnorderedNodes);
+        }
+
+        for connection in &genome.connections {
+            for id in [connection.from, connection.to] {
+                if genome.node(id).is_none() {
+                    return Err(NetworkError::UnknownNode { id });
+                }
+            }
+        }
+
+        // Value slots: inputs first (in id order), then everything else
+        let (inputs, others): (Vec<_>, Vec<_>) = genome
+            .nodes
+            .iter()
+            .partition(|node| node.kind == NodeKind::Input);
+
+        let slots: HashMap<_, _> = inputs
+            .iter()
+            .chain(&others)
+            .enumerate()
+            .map(|(slot, node)| (node.id, slot))
+            .collect();
+
+        let enabled: Vec<_> = genome
+            .connections
+            .iter()
+            .filter(|connection| connection.enabled)
+            .collect();
+
+        // Kahn's algorithm: a node can be evaluated once all of its
+        // inputs have been
+        let mut pending_inputs: HashMap<_, usize> = HashMap::new();
+
+        for connection in &enabled {
+            *pending_inputs.entry(connection.to).or_default() += 1;
+        }
+
+        let mut ready: VecDeque<_> = genome
+            .nodes
+            .iter()
+            .filter(|node| !pending_inputs.contains_key(&node.id))
+            .map(|node| node.id)
+            .collect();
+
+        let mut order = Vec::with_capacity(genome.nodes.len());
+
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+
+            for connection in enabled.iter().filter(|connection| connection.from == id) {
+                let pending = pending_inputs.get_mut(&connection.to).unwrap();
+
+                *pending -= 1;
+
+                if *pending == 0 {
+                    ready.push_back(connection.to);
+                }
+            }
+        }
+
+        if order.len() != genome.nodes.len() {
+            return Err(NetworkError::CyclicGenome);
+        }
+
+        let nodes = order
+            .into_iter()
+            .filter_map(|id| genome.node(id))
+            .filter(|node| node.kind != NodeKind::Input)
+            .map(|node| NeatNode {
+                value: slots[&node.id],
+                bias: node.bias,
+                activation: node.activation,
+                incoming: enabled
+                    .iter()
+                    .filter(|connection| connection.to == node.id)
+                    .map(|connection| (slots[&connection.from], connection.weight))
+                    .collect(),
+            })
+            .collect();
+
+        let outputs = others
+            .iter()
+            .filter(|node| node.kind == NodeKind::Output)
+            .map(|node| slots[&node.id])
+            .collect();
+
+        Ok(Self {
+            inputs: inputs.len(),
+            nodes,
+            outputs,
+            values: slots.len(),
+        })
+    }
+
+    pub fn propogate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        self.try_propogate(inputs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_propogate(&self, inputs: Vec<f32>) -> Result<Vec<f32>, NetworkError> {
+        if inputs.len() != self.inputs {
+            return Err(NetworkError::InputLengthMismatch {
+                expected: self.inputs,
+                actual: inputs.len(),
+            });
+        }
+
+        let mut values = inputs;
+
+        values.resize(self.values, 0.0);
+
+        for node in &self.nodes {
+            let sum = node
+                .incoming
+                .iter()
+                .map(|&(value, weight)| values[value] * weight)
+                .sum::<f32>();
+
+            values[node.value] = node.activation.apply(node.bias + sum);
+        }
+
+        Ok(self.outputs.iter().map(|&value| values[value]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn genome(rng: &mut dyn RngCore, tracker: &mut InnovationTracker) -> Genome {
+        Genome::minimal(
+            rng,
+            tracker,
+            2,
+            1,
+            Activation::Identity,
+            Activation::Identity,
+        )
+    }
+
+    fn innovations(genome: &Genome) -> Vec<usize> {
+        genome
+            .connections()
+            .iter()
+            .map(|connection| connection.innovation)
+            .collect()
+    }
+
+    #[test]
+    fn minimal() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let genome = genome(&mut rng, &mut tracker);
+
+        assert_eq!(genome.nodes().len(), 3);
+        assert_eq!(innovations(&genome), [0, 1]);
+
+        let network = NeatNetwork::from_genome(&genome);
+        let bias = genome.nodes()[2].bias;
+        let w0 = genome.connections()[0].weight;
+        let w1 = genome.connections()[1].weight;
+
+        assert_relative_eq!(
+            network.propogate(vec![2.0, 3.0])[0],
+            bias + 2.0 * w0 + 3.0 * w1
+        );
+    }
+
+    #[test]
+    fn same_mutation_gets_same_innovation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let mut a = genome(&mut rng, &mut tracker);
+        let mut b = a.clone();
+
+        // Both genomes have only two connections, so splitting the first
+        // one in each must yield the same genes
+        a.connections.truncate(1);
+        b.connections.truncate(1);
+
+        assert!(a.mutate_add_node(&mut rng, &mut tracker));
+        assert!(b.mutate_add_node(&mut rng, &mut tracker));
+
+        assert_eq!(innovations(&a), [0, 2, 3]);
+        assert_eq!(innovations(&a), innovations(&b));
+        assert_eq!(a.nodes(), b.nodes());
+    }
+
+    #[test]
+    fn mutate_add_node() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let mut genome = genome(&mut rng, &mut tracker);
+        let before = NeatNetwork::from_genome(&genome).propogate(vec![0.5, -1.0]);
+
+        assert!(genome.mutate_add_node(&mut rng, &mut tracker));
+
+        assert_eq!(genome.nodes().len(), 4);
+        assert_eq!(genome.nodes()[3].kind, NodeKind::Hidden);
+        assert_eq!(genome.connections().len(), 4);
+        assert_eq!(
+            genome
+                .connections()
+                .iter()
+                .filter(|connection| !connection.enabled)
+                .count(),
+            1
+        );
+
+        // With identity activations and zero bias, splitting a connection
+        // doesn't change the output at all
+        let after = NeatNetwork::from_genome(&genome).propogate(vec![0.5, -1.0]);
+
+        assert_relative_eq!(before.as_slice(), after.as_slice());
+    }
+
+    #[test]
+    fn mutate_add_connection() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let mut genome = genome(&mut rng, &mut tracker);
+
+        // Minimal genome is fully connected already
+        assert!(!genome.mutate_add_connection(&mut rng, &mut tracker));
+
+        genome.mutate_add_node(&mut rng, &mut tracker);
+
+        // Now the other input can be connected to the hidden node
+        assert!(genome.mutate_add_connection(&mut rng, &mut tracker));
+        assert!(!genome.mutate_add_connection(&mut rng, &mut tracker));
+        assert_eq!(genome.connections().len(), 5);
+    }
+
+    #[test]
+    fn stays_feed_forward() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(3, 2);
+
+        let mut genome = Genome::minimal(
+            &mut rng,
+            &mut tracker,
+            3,
+            2,
+            Activation::Tanh,
+            Activation::Relu,
+        );
+
+        for _ in 0..50 {
+            genome.mutate_add_node(&mut rng, &mut tracker);
+            genome.mutate_add_connection(&mut rng, &mut tracker);
+            genome.mutate_weights(&mut rng, 0.5, 0.5);
+        }
+
+        let network = NeatNetwork::from_genome(&genome);
+        let output = network.propogate(vec![0.1, 0.2, 0.3]);
+
+        assert_eq!(output.len(), 2);
+        assert!(output.iter().all(|y| (-1.0..=1.0).contains(y)));
+    }
+
+    #[test]
+    fn crossover() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let parent = genome(&mut rng, &mut tracker);
+
+        let mut fitter = parent.clone();
+        let mut other = parent;
+
+        fitter.mutate_add_node(&mut rng, &mut tracker);
+        other.mutate_weights(&mut rng, 1.0, 10.0);
+        other.mutate_add_node(&mut rng, &mut tracker);
+        other.mutate_add_node(&mut rng, &mut tracker);
+
+        let child = Genome::crossover(&mut rng, &fitter, &other);
+
+        // Structure comes from the fitter parent...
+        assert_eq!(innovations(&child), innovations(&fitter));
+
+        let child_ids: Vec<_> = child.nodes().iter().map(|node| node.id).collect();
+        let fitter_ids: Vec<_> = fitter.nodes().iter().map(|node| node.id).collect();
+
+        assert_eq!(child_ids, fitter_ids);
+
+        // ... while matching genes come from both
+        let from_other = child
+            .connections()
+            .iter()
+            .zip(fitter.connections())
+            .filter(|(child, fitter)| child.weight != fitter.weight)
+            .count();
+
+        assert!(from_other > 0);
+
+        let _ = NeatNetwork::from_genome(&child).propogate(vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn try_from_genome() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let genome = genome(&mut rng, &mut tracker);
+
+        let mut unordered = genome.clone();
+
+        unordered.nodes.swap(0, 1);
+
+        let mut dangling = genome.clone();
+
+        dangling.connections[0].to = 7;
+
+        // Output feeding back into an input
+        let mut cyclic = genome.clone();
+
+        cyclic.connections.push(ConnectionGene {
+            innovation: 2,
+            from: 2,
+            to: 0,
+            weight: 1.0,
+            enabled: true,
+        });
+
+        let actual = [unordered, dangling, cyclic]
+            .iter()
+            .map(|genome| NeatNetwork::try_from_genome(genome).unwrap_err())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            actual,
+            [
+                NetworkError::UnorderedNodes,
+                NetworkError::UnknownNode { id: 7 },
+                NetworkError::CyclicGenome,
+            ]
+        );
+    }
+
+    #[test]
+    fn try_propogate() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut tracker = InnovationTracker::new(2, 1);
+        let network = NeatNetwork::from_genome(&genome(&mut rng, &mut tracker));
+
+        assert_eq!(
+            network.try_propogate(vec![1.0]).unwrap_err(),
+            NetworkError::InputLengthMismatch {
+                expected: 2,
+                actual: 1
+            }
+        );
+    }
+}
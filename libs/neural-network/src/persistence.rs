@@ -0,0 +1,440 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::{error, fmt};
+
+/// Version of the on-disk format written by [`Network::to_json()`] and
+/// [`Network::to_bytes()`].
+///
+/// Bump it whenever `NetworkFile` changes shape, so that older binaries
+/// refuse newer files instead of misreading them.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Prefix of every binary-encoded network, followed by the format
+/// version as a little-endian `u32`.
+const MAGIC: &[u8; 4] = b"LTFN";
+
+/// What actually gets stored: the topology (activations included) next
+/// to the flat list of weights, in the same order as
+/// [`Network::weights()`].
+#[derive(Debug, Serialize, Deserialize)]
+struct NetworkFile {
+    version: u32,
+    layers: Vec<LayerTopology>,
+    weights: Vec<f32>,
+}
+
+/// Just enough of [`NetworkFile`] to find out which version we're
+/// dealing with before trying to parse the rest.
+#[derive(Debug, Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+impl Network {
+    /// Topology this network was built from.
+    ///
+    /// The input layer has no neurons of its own, so its activation and
+    /// kind are always reported as the default ones.
+    ///
+    /// Output heads are reported, but initializers aren't (they're not
+    /// part of the network).
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let input = LayerTopology {
+            neurons: self.layers[0].input_size,
+            ..Default::default()
+        };
+
+        once(input)
+            .chain(self.layers.iter().map(|layer| LayerTopology {
+                neurons: layer.output_size(),
+                activation: layer.activation,
+                kind: layer.kind(),
+                heads: layer.heads.clone(),
+                ..Default::default()
+            }))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.to_file()).expect("network is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        let header: VersionHeader = serde_json::from_str(json).map_err(LoadError::Json)?;
+
+        if header.version != FORMAT_VERSION {
+            return Err(unsupported_version(header.version));
+        }
+
+        Self::from_file(serde_json::from_str(json).map_err(LoadError::Json)?)
+    }
+
+    /// Compact binary form: [`MAGIC`], the format version and then the
+    /// bincode-encoded [`NetworkFile`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+
+        bytes.extend(FORMAT_VERSION.to_le_bytes());
+
+        bincode::serialize_into(&mut bytes, &self.to_file())
+            .expect("network is always serializable");
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LoadError> {
+        let bytes = bytes.strip_prefix(MAGIC).ok_or(LoadError::NotANetwork)?;
+
+        if bytes.len() < 4 {
+            return Err(LoadError::NotANetwork);
+        }
+
+        let (version, payload) = bytes.split_at(4);
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+
+        if version != FORMAT_VERSION {
+            return Err(unsupported_version(version));
+        }
+
+        Self::from_file(bincode::deserialize(payload).map_err(LoadError::Binary)?)
+    }
+
+    fn to_file(&self) -> NetworkFile {
+        NetworkFile {
+            version: FORMAT_VERSION,
+            layers: self.topology(),
+            weights: self.weights().collect(),
+        }
+    }
+
+    fn from_file(file: NetworkFile) -> Result<Self, LoadError> {
+        if file.layers.len() < 2 {
+            return Err(LoadError::InvalidTopology {
+                layers: file.layers.len(),
+            });
+        }
+
+        let expected =
+            weight_count(&file.layers).ok_or(LoadError::Invalid(NetworkError::TopologyTooLarge))?;
+
+        if file.weights.len() != expected {
+            return Err(LoadError::ShapeMismatch {
+                expected,
+                actual: file.weights.len(),
+            });
+        }
+
+        Self::try_from_weights(&file.layers, file.weights).map_err(LoadError::Invalid)
+    }
+}
+
+fn unsupported_version(version: u32) -> LoadError {
+    LoadError::UnsupportedVersion {
+        found: version,
+        supported: FORMAT_VERSION,
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// Binary data doesn't start with the expected magic bytes
+    NotANetwork,
+
+    /// File was written by a different version of this crate
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+
+    /// Topology has fewer than two layers (input + output)
+    InvalidTopology {
+        layers: usize,
+    },
+
+    /// Number of stored weights doesn't match the stored topology
+    ShapeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+
+    /// Stored topology is otherwise invalid, e.g. its output heads don't
+    /// add up to the output layer
+    Invalid(NetworkError),
+
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotANetwork => write!(f, "data is not a serialized network"),
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "unsupported network format version {found} (expected {supported})"
+            ),
+            Self::InvalidTopology { layers } => write!(
+                f,
+                "network topology must have at least two layers, got {layers}"
+            ),
+            Self::ShapeMismatch { expected, actual } => write!(
+                f,
+                "network topology requires {expected} weights, but file contains {actual}"
+            ),
+            Self::Invalid(err) => write!(f, "invalid network: {err}"),
+            Self::Json(err) => write!(f, "malformed network json: {err}"),
+            Self::Binary(err) => write!(f, "malformed network binary: {err}"),
+        }
+    }
+}
+
+impl error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            Self::Binary(err) => Some(err),
+            Self::Invalid(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 3,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 4,
+                    activation: Activation::LeakyRelu { alpha: 0.1 },
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 2,
+                    activation: Activation::Tanh,
+                    ..Default::default()
+                },
+            ],
+        )
+    }
+
+    fn assert_same(actual: &Network, expected: &Network) {
+        assert_eq!(actual.topology(), expected.topology());
+
+        let actual: Vec<_> = actual.weights().collect();
+        let expected: Vec<_> = expected.weights().collect();
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let network = network();
+        let loaded = Network::from_json(&network.to_json()).unwrap();
+
+        assert_same(&loaded, &network);
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let network = network();
+        let loaded = Network::from_bytes(&network.to_bytes()).unwrap();
+
+        assert_same(&loaded, &network);
+    }
+
+    #[test]
+    fn json_is_self_describing() {
+        let json = network().to_json();
+
+        assert!(json.starts_with(r#"{"version":1,"layers":[{"neurons":3,"#));
+        assert!(json.contains(r#""activation":{"leaky_relu":{"alpha":0.1}}"#));
+        assert!(json.contains(r#""activation":"tanh""#));
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let json = r#"{"version":2,"something":"else"}"#;
+
+        assert!(matches!(
+            Network::from_json(json),
+            Err(LoadError::UnsupportedVersion {
+                found: 2,
+                supported: 1
+            })
+        ));
+
+        let mut bytes = network().to_bytes();
+        bytes[4] = 2;
+
+        assert!(matches!(
+            Network::from_bytes(&bytes),
+            Err(LoadError::UnsupportedVersion { found: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn heads_roundtrip() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 5,
+                    heads: vec![
+                        OutputHead::new("steering", 2, Activation::Tanh),
+                        OutputHead::new("action", 3, Activation::Softmax),
+                    ],
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let json = network.to_json();
+
+        assert!(json.contains(r#""heads":[{"name":"steering","neurons":2,"activation":"tanh"}"#));
+        assert_same(&Network::from_json(&json).unwrap(), &network);
+        assert_same(&Network::from_bytes(&network.to_bytes()).unwrap(), &network);
+    }
+
+    #[test]
+    fn recurrent_roundtrip() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 3,
+                    activation: Activation::Tanh,
+                    kind: LayerKind::Recurrent,
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let json = network.to_json();
+
+        assert!(json.contains(r#""kind":"recurrent""#));
+        assert_same(&Network::from_json(&json).unwrap(), &network);
+        assert_same(&Network::from_bytes(&network.to_bytes()).unwrap(), &network);
+    }
+
+    #[test]
+    fn rejects_mismatched_shape() {
+        let json = r#"{
+            "version": 1,
+            "layers": [{"neurons": 2, "activation": "relu"}, {"neurons": 1, "activation": "relu"}],
+            "weights": [0.1, 0.2]
+        }"#;
+
+        let err = Network::from_json(json).unwrap_err();
+
+        assert!(matches!(
+            err,
+            LoadError::ShapeMismatch {
+                expected: 3,
+                actual: 2
+            }
+        ));
+
+        assert_eq!(
+            err.to_string(),
+            "network topology requires 3 weights, but file contains 2"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_topology() {
+        let json =
+            r#"{"version": 1, "layers": [{"neurons": 2, "activation": "relu"}], "weights": []}"#;
+
+        assert!(matches!(
+            Network::from_json(json),
+            Err(LoadError::InvalidTopology { layers: 1 })
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_layer() {
+        // 2 * 2^63 weights, which would wrap around to zero
+        let json = r#"{
+            "version": 1,
+            "layers": [{"neurons": 1, "activation": "relu"}, {"neurons": 9223372036854775808, "activation": "relu"}],
+            "weights": []
+        }"#;
+
+        let err = Network::from_json(json).unwrap_err();
+
+        assert!(matches!(
+            err,
+            LoadError::Invalid(NetworkError::TopologyTooLarge)
+        ));
+
+        assert_eq!(
+            err.to_string(),
+            "invalid network: network topology requires too many weights"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_heads() {
+        let json = r#"{
+            "version": 1,
+            "layers": [
+                {"neurons": 1, "activation": "relu"},
+                {"neurons": 2, "activation": "relu", "heads": [{"name": "a", "neurons": 1, "activation": "tanh"}]}
+            ],
+            "weights": [0.1, 0.2, 0.3, 0.4]
+        }"#;
+
+        let err = Network::from_json(json).unwrap_err();
+
+        assert!(matches!(
+            err,
+            LoadError::Invalid(NetworkError::HeadSizeMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+
+        assert_eq!(
+            err.to_string(),
+            "invalid network: output heads have 1 neurons in total, but output layer has 2"
+        );
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(matches!(
+            Network::from_bytes(b"hello world"),
+            Err(LoadError::NotANetwork)
+        ));
+
+        assert!(matches!(
+            Network::from_json("hello world"),
+            Err(LoadError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,464 @@
+use crate::*;
+
+/// What [`Network::prune()`] did.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PruneReport {
+    /// Weights set to zero by magnitude pruning (including ones that were
+    /// later removed together with their neurons)
+    pub weights_zeroed: usize,
+
+    /// Hidden neurons removed, either as dead or as redundant
+    pub neurons_removed: usize,
+
+    /// How many fewer weights and biases the pruned network has
+    pub params_removed: usize,
+
+    /// Largest absolute difference between any output of the original and
+    /// the pruned network on the probe set
+    pub max_deviation: f32,
+
+    /// Average absolute difference between outputs of the original and the
+    /// pruned network on the probe set
+    pub mean_deviation: f32,
+}
+
+impl Network {
+    /// Returns a smaller network approximating this one:
+    ///
+    /// - weights smaller than `threshold` (in magnitude) are set to zero
+    ///   (see [`Self::prune_weights()`]),
+    ///
+    /// - hidden neurons that never activate on `samples` are removed (see
+    ///   [`Self::remove_dead_neurons()`]); pass no samples to skip this
+    ///   step,
+    ///
+    /// - neurons that became redundant are removed (see
+    ///   [`Self::collapse()`]).
+    ///
+    /// `samples` and `probe` hold inputs laid out one after another (as in
+    /// [`Self::propagate_batch()`]); `probe` is used only to measure how
+    /// much outputs changed and can be the same as `samples`.
+    pub fn prune(&self, threshold: f32, samples: &[f32], probe: &[f32]) -> (Network, PruneReport) {
+        let mut pruned = self.clone();
+
+        let weights_zeroed = pruned.prune_weights(threshold);
+
+        let mut neurons_removed = if samples.is_empty() {
+            0
+        } else {
+            pruned.remove_dead_neurons(samples)
+        };
+
+        neurons_removed += pruned.collapse();
+
+        let params_removed = self.weights().count() - pruned.weights().count();
+
+        let mut scratch = Scratch::default();
+        let expected = self.propagate_samples(probe, &mut scratch).to_vec();
+        let actual = pruned.propagate_samples(probe, &mut scratch);
+
+        let deviations: Vec<f32> = expected
+            .iter()
+            .zip(actual)
+            .map(|(expected, actual)| (expected - actual).abs())
+            .collect();
+
+        let max_deviation = deviations.iter().fold(0.0f32, |max, &d| max.max(d));
+
+        let mean_deviation = if deviations.is_empty() {
+            0.0
+        } else {
+            deviations.iter().sum::<f32>() / deviations.len() as f32
+        };
+
+        let report = PruneReport {
+            weights_zeroed,
+            neurons_removed,
+            params_removed,
+            max_deviation,
+            mean_deviation,
+        };
+
+        (pruned, report)
+    }
+
+    /// Sets weights (including recurrent ones, but not biases) smaller
+    /// than `threshold` in magnitude to zero; returns how many weights were
+    /// zeroed.
+    pub fn prune_weights(&mut self, threshold: f32) -> usize {
+        let mut zeroed = 0;
+
+        for layer in &mut self.layers {
+            let recurrent = layer
+                .recurrence
+                .as_mut()
+                .map_or(&mut [][..], |recurrence| &mut recurrence.weights);
+
+            for weight in layer.weights.iter_mut().chain(recurrent) {
+                if *weight != 0.0 && weight.abs() < threshold {
+                    *weight = 0.0;
+                    zeroed += 1;
+                }
+            }
+        }
+
+        zeroed
+    }
+
+    /// Removes hidden neurons that never activate on any of `samples`
+    /// (see [`ActivationStats::dead_neurons()`]), folding their average
+    /// output into the biases of neurons they fed; returns how many
+    /// neurons were removed.
+    ///
+    /// For ReLU that's exact on the samples (dead neurons always output
+    /// zero); for other activations it's an approximation. Every layer
+    /// keeps at least one neuron; softmax layers are left alone.
+    pub fn remove_dead_neurons(&mut self, samples: &[f32]) -> usize {
+        let stats = self.activation_stats(samples);
+        let last = self.layers.len() - 1;
+        let mut removed = 0;
+
+        for (layer, neuron) in stats.dead_neurons().into_iter().rev() {
+            let softmax = self.layers[layer].activation == Activation::Softmax;
+
+            if layer == last || self.layers[layer].output_size() == 1 || softmax {
+                continue;
+            }
+
+            self.remove_neuron(layer, neuron, stats.layers[layer][neuron].mean);
+            removed += 1;
+        }
+
+        removed
+    }
+
+    /// Removes hidden neurons that don't affect the outputs - ones with no
+    /// outgoing (non-zero) weights - or whose outputs are constant - ones
+    /// with no incoming weights, folded into biases of neurons they fed;
+    /// returns how many neurons were removed.
+    ///
+    /// This doesn't change what network computes, so it's meant to be run
+    /// after [`Self::prune_weights()`]. Every layer keeps at least one
+    /// neuron; softmax layers are left alone.
+    pub fn collapse(&mut self) -> usize {
+        let last = self.layers.len() - 1;
+        let mut removed = 0;
+
+        loop {
+            let before = removed;
+
+            for layer_idx in 0..last {
+                for neuron in (0..self.layers[layer_idx].output_size()).rev() {
+                    let layer = &self.layers[layer_idx];
+                    let next = &self.layers[layer_idx + 1];
+
+                    // Removing a neuron would change how softmax normalizes
+                    // the remaining ones
+                    if layer.output_size() == 1 || layer.activation == Activation::Softmax {
+                        break;
+                    }
+
+                    // Neurons feeding their own layer are left alone, since
+                    // their state isn't constant (it starts at zero)
+                    let feeds_back = layer.recurrence.is_some()
+                        && (0..layer.output_size())
+                            .any(|other| layer.recurrent_row(other)[neuron] != 0.0);
+
+                    if feeds_back {
+                        continue;
+                    }
+
+                    let unused =
+                        (0..next.output_size()).all(|other| next.row(other)[neuron] == 0.0);
+
+                    let constant = layer.row(neuron).iter().all(|&w| w == 0.0)
+                        && layer.recurrent_row(neuron).iter().all(|&w| w == 0.0);
+
+                    if unused {
+                        self.remove_neuron(layer_idx, neuron, 0.0);
+                    } else if constant {
+                        let output = layer.activation.apply(layer.biases[neuron]);
+
+                        self.remove_neuron(layer_idx, neuron, output);
+                    } else {
+                        continue;
+                    }
+
+                    removed += 1;
+                }
+            }
+
+            if removed == before {
+                return removed;
+            }
+        }
+    }
+
+    /// Removes given hidden neuron, assuming it always outputs `output`.
+    fn remove_neuron(&mut self, layer: usize, neuron: usize, output: f32) {
+        self.layers[layer].remove_neuron(neuron, output);
+        self.layers[layer + 1].remove_input(neuron, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn topology() -> [LayerTopology; 3] {
+        [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 3,
+                activation: Activation::Relu,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn network() -> Network {
+        Network::from_weights(
+            &topology(),
+            [
+                // Hidden neuron 0: x0 - x1, with a tiny weight on x1
+                0.0, 1.0, -1.0,
+                // Hidden neuron 1: always dead for non-negative inputs
+                -1.0, -0.5, -0.5,
+                // Hidden neuron 2: constant after pruning
+                0.5, 0.001, -0.002,
+                // Output: h0 + 2 * h1 + 4 * h2
+                0.1, 1.0, 2.0, 4.0,
+            ],
+        )
+    }
+
+    fn samples() -> Vec<f32> {
+        vec![
+            1.0, 0.0, //
+            0.0, 1.0, //
+            0.5, 0.5, //
+            1.0, 1.0,
+        ]
+    }
+
+    #[test]
+    fn prune_weights() {
+        let mut network = network();
+        let zeroed = network.prune_weights(0.01);
+
+        assert_eq!(zeroed, 2);
+        assert_eq!(network.weights().filter(|&w| w == 0.0).count(), 3);
+    }
+
+    #[test]
+    fn remove_dead_neurons() {
+        let mut network = network();
+        let expected = network
+            .propagate_batch(&samples(), &mut Scratch::new())
+            .to_vec();
+
+        let removed = network.remove_dead_neurons(&samples());
+
+        assert_eq!(removed, 1);
+        assert_eq!(network.topology()[1].neurons, 2);
+
+        let actual = network
+            .propagate_batch(&samples(), &mut Scratch::new())
+            .to_vec();
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn collapse() {
+        let mut network = network();
+
+        network.prune_weights(0.01);
+
+        let expected = network
+            .propagate_batch(&samples(), &mut Scratch::new())
+            .to_vec();
+
+        // Hidden neuron 2 became constant: relu(0.5) * 4 is now part of the
+        // output's bias
+        assert_eq!(network.collapse(), 1);
+        assert_eq!(network.topology()[1].neurons, 2);
+
+        let actual = network
+            .propagate_batch(&samples(), &mut Scratch::new())
+            .to_vec();
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+
+        let weights: Vec<_> = network.weights().collect();
+
+        #[rustfmt::skip]
+        assert_relative_eq!(
+            weights.as_slice(),
+            [
+                0.0, 1.0, -1.0,
+                -1.0, -0.5, -0.5,
+                2.1, 1.0, 2.0,
+            ]
+            .as_ref()
+        );
+    }
+
+    #[test]
+    fn collapse_removes_unused_neurons() {
+        #[rustfmt::skip]
+        let mut network = Network::from_weights(
+            &topology(),
+            [
+                0.0, 1.0, -1.0,
+                0.3, 0.5, 0.5,
+                0.5, 0.2, -0.2,
+                // Output ignores hidden neurons 1 and 2
+                0.1, 1.0, 0.0, 0.0,
+            ],
+        );
+
+        assert_eq!(network.collapse(), 2);
+        assert_eq!(network.topology()[1].neurons, 1);
+
+        let actual = network.propogate(vec![1.0, 0.5]);
+
+        assert_relative_eq!(actual[0], 0.6);
+    }
+
+    #[test]
+    fn collapse_keeps_one_neuron_per_layer() {
+        let mut network = Network::from_weights(&topology(), [0.0; 13]);
+
+        assert_eq!(network.collapse(), 2);
+        assert_eq!(network.topology()[1].neurons, 1);
+        assert_eq!(network.propogate(vec![1.0, 1.0]), [0.0]);
+    }
+
+    #[test]
+    fn collapse_recurrent() {
+        let layers = [
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Identity,
+                kind: LayerKind::Recurrent,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ];
+
+        #[rustfmt::skip]
+        let mut network = Network::from_weights(
+            &layers,
+            [
+                // Hidden neuron 0: accumulates input
+                0.0, 1.0, 1.0, 1.0,
+                // Hidden neuron 1: constant, but feeds neuron 0 back
+                0.5, 0.0, 0.0, 0.0,
+                // Output
+                0.0, 1.0, 1.0,
+            ],
+        );
+
+        assert_eq!(network.collapse(), 0);
+
+        // Once neuron 1 stops feeding back, it can be folded
+        network.layers[0].recurrence.as_mut().unwrap().weights[1] = 0.0;
+
+        let reference = network.clone();
+
+        assert_eq!(network.collapse(), 1);
+
+        for input in [1.0, 2.0, 3.0] {
+            assert_eq!(
+                network.propogate(vec![input]),
+                reference.propogate(vec![input])
+            );
+        }
+    }
+
+    #[test]
+    fn prune() {
+        let (pruned, report) = network().prune(0.01, &samples(), &samples());
+
+        assert_eq!(report.weights_zeroed, 2);
+        assert_eq!(report.neurons_removed, 2);
+        assert_eq!(report.params_removed, 8);
+
+        // Hidden neuron 2 used to output 0.5 + 0.001 * x0 - 0.002 * x1,
+        // which the output multiplied by 4
+        assert_relative_eq!(report.max_deviation, 0.008, epsilon = 1e-6);
+        assert_relative_eq!(report.mean_deviation, 0.0045, epsilon = 1e-6);
+
+        assert_eq!(pruned.topology()[1].neurons, 1);
+    }
+
+    #[test]
+    fn prune_reports_deviation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let layers = [
+            LayerTopology {
+                neurons: 9,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 18,
+                activation: Activation::Relu,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Tanh,
+                ..Default::default()
+            },
+        ];
+
+        let network = Network::random(&mut rng, &layers);
+        let probe: Vec<f32> = (0..9 * 50).map(|_| rng.gen()).collect();
+
+        let (pruned, report) = network.prune(0.2, &probe, &probe);
+
+        assert!(report.weights_zeroed > 0);
+        assert!(report.max_deviation > 0.0);
+        assert!(report.mean_deviation <= report.max_deviation);
+
+        assert_eq!(
+            report.params_removed,
+            network.weights().count() - pruned.weights().count()
+        );
+
+        // Probe is a batch of 50 samples with 2 outputs each
+        let mut scratch = Scratch::new();
+        let expected = network.propagate_batch(&probe, &mut scratch).to_vec();
+        let actual = pruned.propagate_batch(&probe, &mut scratch);
+
+        let max_deviation = expected
+            .iter()
+            .zip(actual)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+
+        assert_relative_eq!(report.max_deviation, max_deviation);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::*;
+
+/// [`Network`] with weights stored as 8-bit integers (a quarter of the
+/// memory), for approximate inference - see [`Network::quantize()`].
+///
+/// Each layer keeps a single scale factor `s` such that `weight ≈ s * q`
+/// for `q` in `-127..=127`; before a layer runs, its inputs are quantized
+/// the same way (with a scale picked from their largest magnitude), so that
+/// weighted sums are computed entirely on integers. Biases stay `f32`.
+///
+/// # Error bound
+///
+/// For a layer with `n` inputs, weight scale `s` and (true) inputs bounded
+/// by `X` in magnitude, quantization changes each weighted sum by at most
+/// `n * s * X * (254.5 / 254)`; errors coming from the previous layers are
+/// amplified by at most the layer's largest row norm (sum of absolute
+/// weights of a single neuron), then by the activation's steepest slope
+/// (bounded activations cap the error at their range's width).
+/// [`Self::error_bound()`] chains this over all layers - it's a worst-case
+/// guarantee, actual errors are usually orders of magnitude smaller.
+#[derive(Clone, Debug)]
+pub struct QuantizedNetwork {
+    layers: Vec<QuantizedLayer>,
+}
+
+#[derive(Clone, Debug)]
+struct QuantizedLayer {
+    input_size: usize,
+
+    /// Row-major, as in [`Layer`]
+    weights: Vec<i8>,
+    scale: f32,
+    biases: Vec<f32>,
+    activation: Activation,
+    heads: Vec<OutputHead>,
+
+    /// Largest sum of absolute weights of a single neuron, before
+    /// quantization
+    max_row_norm: f32,
+}
+
+impl Network {
+    /// Converts network into an int8 representation; panics if network has
+    /// recurrent layers.
+    pub fn quantize(&self) -> QuantizedNetwork {
+        self.try_quantize().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_quantize(&self) -> Result<QuantizedNetwork, NetworkError> {
+        let layers = self
+            .layers
+            .iter()
+            .map(QuantizedLayer::new)
+            .collect::<Result<_, _>>()?;
+
+        Ok(QuantizedNetwork { layers })
+    }
+}
+
+impl QuantizedNetwork {
+    pub fn propogate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        self.try_propogate(inputs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_propogate(&self, inputs: Vec<f32>) -> Result<Vec<f32>, NetworkError> {
+        self.check_input_len(inputs.len())?;
+
+        Ok(self
+            .propagate_into(&inputs, &mut Scratch::default())
+            .to_vec())
+    }
+
+    /// Like [`Network::propagate_into()`] - doesn't allocate once `scratch`
+    /// has been used with this network.
+    pub fn propagate_into<'a>(&self, inputs: &[f32], scratch: &'a mut Scratch) -> &'a [f32] {
+        if let Err(err) = self.check_input_len(inputs.len()) {
+            panic!("{err}");
+        }
+
+        let Scratch {
+            front,
+            back,
+            quantized,
+            ..
+        } = scratch;
+
+        front.clear();
+        front.extend_from_slice(inputs);
+
+        for layer in &self.layers {
+            layer.propagate_into(front, quantized, back);
+            mem::swap(front, back);
+        }
+
+        front
+    }
+
+    /// Largest possible difference between any of this network's outputs
+    /// and the corresponding output of the original network, given inputs
+    /// within `[-max_input, max_input]`.
+    ///
+    /// Bounded activations (e.g. tanh) cap the error at their range's
+    /// width - that's the only guarantee for [`Activation::Step`], which
+    /// can flip on an arbitrarily small change.
+    pub fn error_bound(&self, max_input: f32) -> f32 {
+        let mut magnitude = max_input.abs();
+        let mut error = 0.0;
+
+        for layer in &self.layers {
+            let n = layer.input_size as f32;
+
+            let sum_error = layer.max_row_norm * error
+                + n * layer.scale * (magnitude + error) * (254.5 / 254.0);
+
+            let max_bias = layer.biases.iter().fold(0.0f32, |max, b| max.max(b.abs()));
+            let sum_magnitude = max_bias + layer.max_row_norm * magnitude;
+
+            // With output heads, the worst one decides
+            let (slope, output_magnitude, max_error) =
+                segments(layer.activation, &layer.heads, layer.biases.len())
+                    .map(|(_, activation)| bounds(activation, sum_magnitude))
+                    .fold((0.0, 0.0, 0.0), |(a, b, c), (x, y, z)| {
+                        (f32::max(a, x), f32::max(b, y), f32::max(c, z))
+                    });
+
+            error = (slope * sum_error).min(max_error);
+            magnitude = output_magnitude;
+        }
+
+        error
+    }
+
+    fn check_input_len(&self, actual: usize) -> Result<(), NetworkError> {
+        let expected = self.layers[0].input_size;
+
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(NetworkError::InputLengthMismatch { expected, actual })
+        }
+    }
+}
+
+impl QuantizedLayer {
+    fn new(layer: &Layer) -> Result<Self, NetworkError> {
+        if layer.recurrence.is_some() {
+            return Err(NetworkError::RecurrentQuantization);
+        }
+
+        let max_weight = layer.weights.iter().fold(0.0f32, |max, w| max.max(w.abs()));
+
+        let scale = max_weight / 127.0;
+        let weights = quantize(&layer.weights, scale).collect();
+
+        let max_row_norm = (0..layer.output_size())
+            .map(|neuron| layer.row(neuron).iter().map(|w| w.abs()).sum::<f32>())
+            .fold(0.0, f32::max);
+
+        Ok(Self {
+            input_size: layer.input_size,
+            weights,
+            scale,
+            biases: layer.biases.clone(),
+            activation: layer.activation,
+            heads: layer.heads.clone(),
+            max_row_norm,
+        })
+    }
+
+    fn propagate_into(&self, inputs: &[f32], quantized: &mut Vec<i8>, outputs: &mut Vec<f32>) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        let input_scale = inputs.iter().fold(0.0f32, |max, x| max.max(x.abs())) / 127.0;
+
+        quantized.clear();
+        quantized.extend(quantize(inputs, input_scale));
+
+        let scale = self.scale * input_scale;
+
+        outputs.clear();
+
+        if self.input_size == 0 {
+            outputs.extend_from_slice(&self.biases);
+        } else {
+            let rows = self.weights.chunks_exact(self.input_size);
+
+            outputs.extend(
+                rows.zip(&self.biases)
+                    .map(|(row, bias)| bias + scale * dot(row, quantized) as f32),
+            );
+        }
+
+        for (range, activation) in segments(self.activation, &self.heads, outputs.len()) {
+            activation.apply_all(&mut outputs[range]);
+        }
+    }
+}
+
+/// How an activation affects errors of weighted sums bounded by
+/// `sum_magnitude`: its steepest slope, largest output magnitude and the
+/// largest error it can produce - bounded activations can't be off by more
+/// than their range.
+fn bounds(activation: Activation, sum_magnitude: f32) -> (f32, f32, f32) {
+    match activation {
+        Activation::Relu | Activation::Identity => (1.0, sum_magnitude, f32::INFINITY),
+        Activation::LeakyRelu { alpha } => {
+            let slope = alpha.abs().max(1.0);
+
+            (slope, slope * sum_magnitude, f32::INFINITY)
+        }
+        Activation::Sigmoid => (0.25, 1.0, 1.0),
+        Activation::Tanh | Activation::Softsign => (1.0, 1.0, 2.0),
+        Activation::Step => (f32::INFINITY, 1.0, 1.0),
+
+        // Each output depends on all of the sums, but changing each of
+        // them by at most `e` changes any output by at most `e / 2`
+        Activation::Softmax => (0.5, 1.0, 1.0),
+    }
+}
+
+/// Maps `values` onto `-127..=127`, so that `value ≈ scale * quantized`;
+/// `scale` must be large enough for values to fit.
+fn quantize(values: &[f32], scale: f32) -> impl Iterator<Item = i8> + '_ {
+    // Subnormal scales would overflow the inverse, but values that small
+    // can be safely treated as zeros
+    let inverse = if scale.is_normal() { 1.0 / scale } else { 0.0 };
+
+    // Adding 1.5 * 2^23 pushes the fraction out of the mantissa, leaving
+    // `value * inverse` rounded to nearest integer in mantissa's lowest
+    // bits - unlike `f32::round()` and saturating `as` casts, this compiles
+    // down to a few SIMD instructions.
+    values
+        .iter()
+        .map(move |&value| ((value * inverse + 12_582_912.0).to_bits() as i32 - 0x4B40_0000) as i8)
+}
+
+/// Integer counterpart of `layer::dot()`.
+///
+/// Products of two values within `-127..=127` fit in `i16`, which is much
+/// cheaper to multiply than `i32` on baseline x86-64 (SSE2).
+fn dot(a: &[i8], b: &[i8]) -> i32 {
+    const LANES: usize = 8;
+
+    let mut sums = [0i32; LANES];
+    let a_chunks = a.chunks_exact(LANES);
+    let b_chunks = b.chunks_exact(LANES);
+    let tail = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(&a, &b)| (a as i16 * b as i16) as i32)
+        .sum::<i32>();
+
+    for (a, b) in a_chunks.zip(b_chunks) {
+        for lane in 0..LANES {
+            sums[lane] += (a[lane] as i16 * b[lane] as i16) as i32;
+        }
+    }
+
+    sums.iter().sum::<i32>() + tail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// The bird brain's shape
+    fn topology(cells: usize) -> [LayerTopology; 3] {
+        [
+            LayerTopology {
+                neurons: cells,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2 * cells,
+                activation: Activation::Relu,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Tanh,
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn stays_within_error_bound() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for cells in [1, 9, 25] {
+            let network = Network::random(&mut rng, &topology(cells));
+            let quantized = network.quantize();
+            let bound = quantized.error_bound(1.0);
+
+            let mut max_error = 0.0f32;
+
+            for _ in 0..500 {
+                let inputs: Vec<f32> = (0..cells).map(|_| rng.gen_range(-1.0..=1.0)).collect();
+
+                let expected = network.propogate(inputs.clone());
+                let actual = quantized.propogate(inputs);
+
+                for (actual, expected) in actual.iter().zip(&expected) {
+                    max_error = max_error.max((actual - expected).abs());
+                }
+            }
+
+            assert!(
+                max_error <= bound,
+                "cells = {cells}, max_error = {max_error}, bound = {bound}"
+            );
+
+            // Default bird brain (9 eye cells) steers almost exactly the
+            // same, even though the worst-case bound is way looser
+            if cells <= 9 {
+                assert!(max_error < 0.05, "cells = {cells}, max_error = {max_error}");
+            }
+        }
+    }
+
+    #[test]
+    fn heads() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut layers = topology(9);
+
+        layers[2].neurons = 5;
+        layers[2].heads = vec![
+            OutputHead::new("steering", 2, Activation::Tanh),
+            OutputHead::new("action", 3, Activation::Softmax),
+        ];
+
+        let network = Network::random(&mut rng, &layers);
+        let quantized = network.quantize();
+        let bound = quantized.error_bound(1.0);
+
+        for _ in 0..100 {
+            let inputs: Vec<f32> = (0..9).map(|_| rng.gen_range(-1.0..=1.0)).collect();
+
+            let expected = network.propogate(inputs.clone());
+            let actual = quantized.propogate(inputs);
+
+            assert_relative_eq!(actual[2..].iter().sum::<f32>(), 1.0, epsilon = 1e-6);
+
+            for (actual, expected) in actual.iter().zip(&expected) {
+                assert!((actual - expected).abs() <= bound);
+            }
+        }
+    }
+
+    #[test]
+    fn error_bound() {
+        let layers = [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ];
+
+        let network = Network::from_weights(&layers, [0.5, 1.27, -0.5]);
+        let quantized = network.quantize();
+
+        // n * s * X * (254.5 / 254), where s = 1.27 / 127
+        assert_relative_eq!(
+            quantized.error_bound(2.0),
+            2.0 * 0.01 * 2.0 * (254.5 / 254.0)
+        );
+
+        let layers = [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Step,
+                ..Default::default()
+            },
+        ];
+
+        let network = Network::from_weights(&layers, [0.5, 1.0, -0.5]);
+
+        assert_relative_eq!(network.quantize().error_bound(1.0), 1.0);
+    }
+
+    #[test]
+    fn exact_for_representable_weights() {
+        let layers = [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ];
+
+        // All weights are multiples of 1.27 / 127 and inputs are multiples
+        // of 2.54 / 127
+        let network = Network::from_weights(&layers, [0.5, 1.27, -0.5, 0.1, 0.02, 0.63]);
+        let actual = network.quantize().propogate(vec![2.54, -1.0]);
+        let expected = network.propogate(vec![2.54, -1.0]);
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-5);
+    }
+
+    #[test]
+    fn propagate_into() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = Network::random(&mut rng, &topology(9)).quantize();
+        let mut scratch = Scratch::new();
+
+        for _ in 0..3 {
+            let inputs: Vec<f32> = (0..9).map(|_| rng.gen()).collect();
+            let expected = network.propogate(inputs.clone());
+            let actual = network.propagate_into(&inputs, &mut scratch);
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn zeros() {
+        let network = Network::from_weights(&topology(2), [0.0; 22]).quantize();
+        let actual = network.propogate(vec![0.0, 0.0]);
+
+        assert_eq!(actual, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn try_quantize() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut layers = topology(2);
+
+        layers[1].kind = LayerKind::Recurrent;
+
+        let network = Network::random(&mut rng, &layers);
+
+        assert_eq!(
+            network.try_quantize().unwrap_err(),
+            NetworkError::RecurrentQuantization
+        );
+    }
+
+    #[test]
+    fn try_propogate() {
+        let network = Network::from_weights(&topology(2), [0.0; 22]).quantize();
+
+        assert_eq!(
+            network.try_propogate(vec![1.0]),
+            Err(NetworkError::InputLengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use crate::*;
+use std::fmt::Write;
+
+/// Colour of edges with positive weights
+const POSITIVE: &str = "#1f77b4";
+
+/// Colour of edges with negative weights
+const NEGATIVE: &str = "#d62728";
+
+/// Edge thickness of the weakest and strongest weight
+const MIN_WIDTH: f32 = 0.25;
+const MAX_WIDTH: f32 = 4.0;
+
+/// SVG layout, in pixels
+const RADIUS: f32 = 14.0;
+const COLUMN_GAP: f32 = 160.0;
+const ROW_GAP: f32 = 44.0;
+const MARGIN: f32 = 40.0;
+
+impl Network {
+    /// Renders network in Graphviz's DOT format.
+    ///
+    /// Neurons are labelled with their biases, edges are coloured by
+    /// their weight's sign (blue = positive, red = negative) and
+    /// thickened by its magnitude; recurrent connections are dashed.
+    pub fn to_dot(&self) -> String {
+        self.render_dot(None)
+    }
+
+    /// Like [`Self::to_dot()`], but additionally shows (and shades
+    /// neurons by) what each neuron outputs for given inputs.
+    ///
+    /// Recurrent layers start from zero state.
+    pub fn to_dot_with_activations(&self, inputs: &[f32]) -> String {
+        self.render_dot(Some(self.activations(inputs)))
+    }
+
+    /// Renders network as a standalone SVG image, following the same
+    /// conventions as [`Self::to_dot()`].
+    pub fn to_svg(&self) -> String {
+        self.render_svg(None)
+    }
+
+    /// Like [`Self::to_svg()`], but additionally shows (and shades
+    /// neurons by) what each neuron outputs for given inputs.
+    ///
+    /// Recurrent layers start from zero state.
+    pub fn to_svg_with_activations(&self, inputs: &[f32]) -> String {
+        self.render_svg(Some(self.activations(inputs)))
+    }
+
+    /// Outputs of every layer, input layer included.
+    fn activations(&self, inputs: &[f32]) -> Vec<Vec<f32>> {
+        let trace = self.trace(inputs);
+
+        once(trace.inputs)
+            .chain(trace.layers.into_iter().map(|layer| layer.post_activation))
+            .collect()
+    }
+
+    fn render_dot(&self, activations: Option<Vec<Vec<f32>>>) -> String {
+        let max_weight = self.max_weight();
+        let max_activation = activations.as_deref().map(max_abs);
+        let mut dot = String::new();
+
+        dot.push_str("digraph network {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    splines=line;\n");
+        dot.push_str("    node [shape=circle, style=filled, fillcolor=white, fontsize=10];\n");
+
+        for (layer_idx, size) in self.layer_sizes().enumerate() {
+            let title = match layer_idx {
+                0 => "input".to_string(),
+                _ => {
+                    let layer = &self.layers[layer_idx - 1];
+
+                    format!("layer {layer_idx} ({:?})", layer.activation)
+                }
+            };
+
+            let _ = writeln!(dot, "    subgraph cluster_{layer_idx} {{");
+            let _ = writeln!(dot, "        label=\"{title}\";");
+            dot.push_str("        color=lightgrey;\n");
+
+            for neuron in 0..size {
+                let mut label = match layer_idx {
+                    0 => format!("in {neuron}"),
+                    _ => format!("b={:.2}", self.layers[layer_idx - 1].biases[neuron]),
+                };
+
+                let mut fill = "white".to_string();
+
+                if let (Some(activations), Some(max)) = (&activations, max_activation) {
+                    let value = activations[layer_idx][neuron];
+
+                    let _ = write!(label, "\\n{value:.2}");
+                    fill = shade(value, max);
+                }
+
+                let _ = writeln!(
+                    dot,
+                    "        {} [label=\"{label}\", fillcolor=\"{fill}\"];",
+                    node_id(layer_idx, neuron)
+                );
+            }
+
+            dot.push_str("    }\n");
+        }
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            for neuron in 0..layer.output_size() {
+                let to = node_id(layer_idx + 1, neuron);
+
+                for (input, &weight) in layer.row(neuron).iter().enumerate() {
+                    let _ = writeln!(
+                        dot,
+                        "    {} -> {to} [{}];",
+                        node_id(layer_idx, input),
+                        edge_style(weight, max_weight)
+                    );
+                }
+
+                for (input, &weight) in layer.recurrent_row(neuron).iter().enumerate() {
+                    let _ = writeln!(
+                        dot,
+                        "    {} -> {to} [{}, style=dashed, constraint=false];",
+                        node_id(layer_idx + 1, input),
+                        edge_style(weight, max_weight)
+                    );
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn render_svg(&self, activations: Option<Vec<Vec<f32>>>) -> String {
+        let max_weight = self.max_weight();
+        let max_activation = activations.as_deref().map(max_abs);
+        let sizes: Vec<_> = self.layer_sizes().collect();
+        let tallest = sizes.iter().copied().max().unwrap_or(0).max(1);
+
+        let width = 2.0 * MARGIN + COLUMN_GAP * (sizes.len() - 1) as f32;
+        let height = 2.0 * MARGIN + ROW_GAP * (tallest - 1) as f32;
+
+        // Centers each layer vertically
+        let position = |layer: usize, neuron: usize| {
+            let offset = (tallest - sizes[layer]) as f32 * ROW_GAP / 2.0;
+
+            (
+                MARGIN + COLUMN_GAP * layer as f32,
+                MARGIN + offset + ROW_GAP * neuron as f32,
+            )
+        };
+
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+        );
+
+        let _ = writeln!(
+            svg,
+            "  <rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>"
+        );
+
+        // Edges go first, so that neurons are drawn on top of them
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            for neuron in 0..layer.output_size() {
+                let (x2, y2) = position(layer_idx + 1, neuron);
+
+                for (input, &weight) in layer.row(neuron).iter().enumerate() {
+                    let (x1, y1) = position(layer_idx, input);
+
+                    let _ = writeln!(
+                        svg,
+                        "  <line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{}\" stroke-width=\"{:.2}\"/>",
+                        edge_color(weight),
+                        edge_width(weight, max_weight)
+                    );
+                }
+
+                for (input, &weight) in layer.recurrent_row(neuron).iter().enumerate() {
+                    let (x1, y1) = position(layer_idx + 1, input);
+
+                    // Bulge to the right, so that edges between neurons of
+                    // the same column (and self-loops) remain visible
+                    let bulge = RADIUS * 2.5 + (y2 - y1).abs() / 4.0;
+
+                    let _ = writeln!(
+                        svg,
+                        "  <path d=\"M {x1} {y1} C {} {y1}, {} {y2}, {x2} {y2}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.2}\" stroke-dasharray=\"4 3\"/>",
+                        x1 + bulge,
+                        x2 + bulge,
+                        edge_color(weight),
+                        edge_width(weight, max_weight)
+                    );
+                }
+            }
+        }
+
+        for (layer_idx, &size) in sizes.iter().enumerate() {
+            for neuron in 0..size {
+                let (x, y) = position(layer_idx, neuron);
+
+                let fill = match (&activations, max_activation) {
+                    (Some(activations), Some(max)) => shade(activations[layer_idx][neuron], max),
+                    _ => "white".to_string(),
+                };
+
+                let _ = writeln!(
+                    svg,
+                    "  <circle cx=\"{x}\" cy=\"{y}\" r=\"{RADIUS}\" fill=\"{fill}\" stroke=\"black\"/>"
+                );
+
+                if layer_idx > 0 {
+                    let bias = self.layers[layer_idx - 1].biases[neuron];
+
+                    let _ = writeln!(
+                        svg,
+                        "  <text x=\"{x}\" y=\"{}\" font-size=\"9\" text-anchor=\"middle\">b={bias:.2}</text>",
+                        y + RADIUS + 10.0
+                    );
+                }
+
+                if let Some(activations) = &activations {
+                    let _ = writeln!(
+                        svg,
+                        "  <text x=\"{x}\" y=\"{}\" font-size=\"9\" text-anchor=\"middle\">{:.2}</text>",
+                        y + 3.0,
+                        activations[layer_idx][neuron]
+                    );
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Number of neurons in each layer, input layer included.
+    fn layer_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        once(self.layers[0].input_size).chain(self.layers.iter().map(|layer| layer.output_size()))
+    }
+
+    /// Largest weight magnitude (biases excluded), used to scale edges.
+    fn max_weight(&self) -> f32 {
+        self.layers
+            .iter()
+            .flat_map(|layer| {
+                let recurrent = layer.recurrence.iter().flat_map(|r| &r.weights);
+
+                layer.weights.iter().chain(recurrent)
+            })
+            .fold(0.0, |max: f32, weight| max.max(weight.abs()))
+    }
+}
+
+fn node_id(layer: usize, neuron: usize) -> String {
+    format!("n{layer}_{neuron}")
+}
+
+fn edge_color(weight: f32) -> &'static str {
+    if weight < 0.0 {
+        NEGATIVE
+    } else {
+        POSITIVE
+    }
+}
+
+fn edge_width(weight: f32, max_weight: f32) -> f32 {
+    if max_weight > 0.0 {
+        MIN_WIDTH + (MAX_WIDTH - MIN_WIDTH) * weight.abs() / max_weight
+    } else {
+        MIN_WIDTH
+    }
+}
+
+fn edge_style(weight: f32, max_weight: f32) -> String {
+    format!(
+        "color=\"{}\", penwidth={:.2}, tooltip=\"{weight:.3}\"",
+        edge_color(weight),
+        edge_width(weight, max_weight)
+    )
+}
+
+fn max_abs(values: &[Vec<f32>]) -> f32 {
+    values
+        .iter()
+        .flatten()
+        .fold(0.0, |max: f32, value| max.max(value.abs()))
+}
+
+/// Background of a neuron that outputs `value`: the stronger the output,
+/// the more saturated the colour (blue = positive, red = negative).
+fn shade(value: f32, max: f32) -> String {
+    let strength = if max > 0.0 {
+        (value.abs() / max).min(1.0)
+    } else {
+        0.0
+    };
+
+    // Blend from white towards the edge colours
+    let (r, g, b) = if value < 0.0 {
+        (0xd6, 0x27, 0x28)
+    } else {
+        (0x1f, 0x77, 0xb4)
+    };
+
+    let blend = |channel: u8| (255.0 - (255.0 - channel as f32) * strength).round() as u8;
+
+    format!("#{:02x}{:02x}{:02x}", blend(r), blend(g), blend(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network() -> Network {
+        let layers = [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                ..Default::default()
+            },
+        ];
+
+        // bias, then one positive and one (stronger) negative weight
+        Network::from_weights(&layers, [0.5, 1.0, -2.0])
+    }
+
+    #[test]
+    fn to_dot() {
+        let dot = network().to_dot();
+
+        assert!(dot.starts_with("digraph network {"));
+        assert!(dot.contains("n0_0 [label=\"in 0\""));
+        assert!(dot.contains("n1_0 [label=\"b=0.50\""));
+        assert!(dot.contains("n0_0 -> n1_0 [color=\"#1f77b4\", penwidth=2.12"));
+        assert!(dot.contains("n0_1 -> n1_0 [color=\"#d62728\", penwidth=4.00"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn to_dot_with_activations() {
+        let dot = network().to_dot_with_activations(&[1.0, 1.0]);
+
+        // 0.5 + 1.0 - 2.0 = -0.5
+        assert!(dot.contains("n1_0 [label=\"b=0.50\\n-0.50\""));
+
+        // Inputs are the strongest activations, so they're fully saturated
+        assert!(dot.contains("n0_0 [label=\"in 0\\n1.00\", fillcolor=\"#1f77b4\"]"));
+    }
+
+    #[test]
+    fn to_dot_recurrent() {
+        let layers = [
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                kind: LayerKind::Recurrent,
+                ..Default::default()
+            },
+        ];
+
+        let dot = Network::from_weights(&layers, [0.0, 1.0, -1.0]).to_dot();
+
+        assert!(dot.contains("n1_0 -> n1_0 [color=\"#d62728\", penwidth=4.00"));
+        assert!(dot.contains("style=dashed"));
+    }
+
+    #[test]
+    fn to_svg() {
+        let svg = network().to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert_eq!(svg.matches("stroke=\"#d62728\"").count(), 1);
+        assert!(svg.contains(">b=0.50</text>"));
+    }
+
+    #[test]
+    fn to_svg_with_activations() {
+        let svg = network().to_svg_with_activations(&[1.0, 1.0]);
+
+        assert!(svg.contains(">-0.50</text>"));
+        assert_eq!(svg.matches("fill=\"#1f77b4\"").count(), 2);
+    }
+}
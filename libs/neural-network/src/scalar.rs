@@ -0,0 +1,251 @@
+use std::{
+    fmt::Debug,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub},
+};
+
+/// Number type a [`Network`](crate::Network) computes with - `f32` by
+/// default, `f64` for extra precision, or anything else (e.g. a fixed-point
+/// type) providing basic arithmetic and conversions from/to `f64`.
+///
+/// Functions used by activations come with default implementations going
+/// through `f64`; `f32` and `f64` use their native ones instead.
+pub trait Scalar:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + Sum
+    + 'static
+{
+    fn from_f64(value: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+
+    fn zero() -> Self {
+        Self::from_f64(0.0)
+    }
+
+    fn one() -> Self {
+        Self::from_f64(1.0)
+    }
+
+    fn abs(self) -> Self {
+        if self < Self::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if self < other {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn exp(self) -> Self {
+        Self::from_f64(self.to_f64().exp())
+    }
+
+    fn tanh(self) -> Self {
+        Self::from_f64(self.to_f64().tanh())
+    }
+}
+
+macro_rules! impl_float {
+    ($ty:ty) => {
+        impl Scalar for $ty {
+            fn from_f64(value: f64) -> Self {
+                value as $ty
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn zero() -> Self {
+                0.0
+            }
+
+            fn one() -> Self {
+                1.0
+            }
+
+            fn abs(self) -> Self {
+                <$ty>::abs(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                <$ty>::max(self, other)
+            }
+
+            fn exp(self) -> Self {
+                <$ty>::exp(self)
+            }
+
+            fn tanh(self) -> Self {
+                <$ty>::tanh(self)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use approx::assert_relative_eq;
+
+    /// Q16.16 fixed-point number - the kind of type embedded targets
+    /// without an FPU would use.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+    struct Fixed(i32);
+
+    const ONE: i64 = 1 << 16;
+
+    impl Add for Fixed {
+        type Output = Self;
+
+        fn add(self, rhs: Self) -> Self {
+            Self(self.0 + rhs.0)
+        }
+    }
+
+    impl Sub for Fixed {
+        type Output = Self;
+
+        fn sub(self, rhs: Self) -> Self {
+            Self(self.0 - rhs.0)
+        }
+    }
+
+    impl Mul for Fixed {
+        type Output = Self;
+
+        fn mul(self, rhs: Self) -> Self {
+            Self((self.0 as i64 * rhs.0 as i64 / ONE) as i32)
+        }
+    }
+
+    impl Div for Fixed {
+        type Output = Self;
+
+        fn div(self, rhs: Self) -> Self {
+            Self((self.0 as i64 * ONE / rhs.0 as i64) as i32)
+        }
+    }
+
+    impl Neg for Fixed {
+        type Output = Self;
+
+        fn neg(self) -> Self {
+            Self(-self.0)
+        }
+    }
+
+    impl AddAssign for Fixed {
+        fn add_assign(&mut self, rhs: Self) {
+            self.0 += rhs.0;
+        }
+    }
+
+    impl Sum for Fixed {
+        fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+            iter.fold(Self(0), Add::add)
+        }
+    }
+
+    impl Scalar for Fixed {
+        fn from_f64(value: f64) -> Self {
+            Self((value * ONE as f64).round() as i32)
+        }
+
+        fn to_f64(self) -> f64 {
+            self.0 as f64 / ONE as f64
+        }
+    }
+
+    fn layers() -> [LayerTopology; 3] {
+        [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Relu,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Tanh,
+                ..Default::default()
+            },
+        ]
+    }
+
+    const WEIGHTS: [f64; 9] = [0.0, 1.0, -1.0, 0.5, -0.25, 0.75, 0.1, 1.0, 2.0];
+
+    #[test]
+    fn f64() {
+        let network = Network::<f64>::from_scalar_weights(&layers(), WEIGHTS);
+        let actual = network.propogate(vec![0.5, -0.5]);
+
+        // relu(0.5 + 0.5) + 2 * relu(0.5 - 0.125 - 0.375) = 1
+        assert_relative_eq!(actual[0], (0.1f64 + 1.0).tanh());
+    }
+
+    #[test]
+    fn f64_matches_f32() {
+        let network = Network::from_weights(&layers(), WEIGHTS.map(|w| w as f32));
+        let expected = network.propogate(vec![0.3, 0.8]);
+
+        let network = Network::<f64>::from_scalar_weights(&layers(), WEIGHTS);
+        let actual = network.propogate(vec![0.3, 0.8]);
+
+        assert_relative_eq!(actual[0] as f32, expected[0], epsilon = 1e-6);
+    }
+
+    #[test]
+    fn fixed_point() {
+        let weights = WEIGHTS.map(Fixed::from_f64);
+        let network = Network::<Fixed>::from_scalar_weights(&layers(), weights);
+
+        let inputs = vec![Fixed::from_f64(0.3), Fixed::from_f64(0.8)];
+        let actual = network.propogate(inputs)[0].to_f64();
+
+        let network = Network::<f64>::from_scalar_weights(&layers(), WEIGHTS);
+        let expected = network.propogate(vec![0.3, 0.8])[0];
+
+        assert_relative_eq!(actual, expected, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn random_networks_share_seeds() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let a = Network::random(&mut ChaCha8Rng::from_seed(Default::default()), &layers());
+        let b = Network::<f64>::random_scalar(
+            &mut ChaCha8Rng::from_seed(Default::default()),
+            &layers(),
+        );
+
+        let a: Vec<_> = a.weights().map(|w| w as f64).collect();
+        let b: Vec<_> = b.weights().collect();
+
+        assert_eq!(a, b);
+    }
+}
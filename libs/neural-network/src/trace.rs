@@ -0,0 +1,275 @@
+use crate::*;
+
+/// Everything that happened inside the network during a single
+/// propagation.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Trace {
+    pub inputs: Vec<f32>,
+
+    /// One entry per layer, input layer excluded
+    pub layers: Vec<LayerTrace>,
+}
+
+impl Trace {
+    /// Network's final outputs - same as `propogate()` would return.
+    pub fn outputs(&self) -> &[f32] {
+        self.layers
+            .last()
+            .map_or(&self.inputs, |layer| &layer.post_activation)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LayerTrace {
+    /// Weighted sums (biases included) of each neuron
+    pub pre_activation: Vec<f32>,
+
+    /// Outputs of each neuron, i.e. `pre_activation` passed through the
+    /// layer's activation
+    pub post_activation: Vec<f32>,
+}
+
+/// How neurons behaved across a batch of inputs.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ActivationStats {
+    pub samples: usize,
+
+    /// `layers[layer][neuron]`, input layer excluded
+    pub layers: Vec<Vec<NeuronStats>>,
+}
+
+impl ActivationStats {
+    /// `(layer, neuron)` of each neuron that was inactive for every sample
+    /// - with ReLU, such a neuron always outputs zero.
+    pub fn dead_neurons(&self) -> Vec<(usize, usize)> {
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(layer_idx, layer)| {
+                layer
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, neuron)| neuron.inactive == self.samples)
+                    .map(move |(neuron_idx, _)| (layer_idx, neuron_idx))
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NeuronStats {
+    /// Smallest output
+    pub min: f32,
+
+    /// Largest output
+    pub max: f32,
+
+    /// Average output
+    pub mean: f32,
+
+    /// For how many samples the weighted sum was not positive (i.e. for
+    /// how many samples a ReLU neuron would be "dead")
+    pub inactive: usize,
+}
+
+impl Network {
+    /// Like [`Self::propogate()`], but returns what each layer computed
+    /// instead of just the final outputs.
+    ///
+    /// Just like `propogate()`, starts recurrent layers from zero state.
+    pub fn propagate_traced(&self, inputs: &[f32]) -> Trace {
+        self.trace(inputs)
+    }
+
+    /// Propagates each of the samples laid out one after another in
+    /// `inputs` (as in [`Self::propagate_batch()`]) and summarizes what
+    /// every neuron did.
+    ///
+    /// Samples are independent of each other: recurrent layers start from
+    /// zero state for each one.
+    pub fn activation_stats(&self, inputs: &[f32]) -> ActivationStats {
+        let input_size = self.layers[0].input_size;
+
+        assert!(input_size > 0, "cannot batch inputs of a zero-sized layer");
+        assert_eq!(
+            inputs.len() % input_size,
+            0,
+            "got {} inputs, which is not a multiple of input size {}",
+            inputs.len(),
+            input_size
+        );
+
+        let samples = inputs.len() / input_size;
+
+        let mut layers: Vec<Vec<_>> = self
+            .layers
+            .iter()
+            .map(|layer| {
+                vec![
+                    NeuronStats {
+                        min: f32::INFINITY,
+                        max: f32::NEG_INFINITY,
+                        mean: 0.0,
+                        inactive: 0,
+                    };
+                    layer.output_size()
+                ]
+            })
+            .collect();
+
+        for sample in inputs.chunks_exact(input_size) {
+            let trace = self.trace(sample);
+
+            for (stats, trace) in layers.iter_mut().zip(&trace.layers) {
+                let values = trace.pre_activation.iter().zip(&trace.post_activation);
+
+                for (stats, (&pre, &post)) in stats.iter_mut().zip(values) {
+                    stats.min = stats.min.min(post);
+                    stats.max = stats.max.max(post);
+                    stats.mean += post / samples as f32;
+
+                    if pre <= 0.0 {
+                        stats.inactive += 1;
+                    }
+                }
+            }
+        }
+
+        ActivationStats { samples, layers }
+    }
+
+    pub(crate) fn trace(&self, inputs: &[f32]) -> Trace {
+        if let Err(err) = self.check_input_len(inputs.len()) {
+            panic!("{err}");
+        }
+
+        let mut layers: Vec<LayerTrace> = Vec::with_capacity(self.layers.len());
+
+        for layer in &self.layers {
+            let inputs = layers.last().map_or(inputs, |layer| &layer.post_activation);
+
+            let (pre_activation, post_activation) = layer.propagate_traced(inputs);
+
+            layers.push(LayerTrace {
+                pre_activation,
+                post_activation,
+            });
+        }
+
+        Trace {
+            inputs: inputs.to_vec(),
+            layers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn network() -> Network {
+        let layers = [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Relu,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Tanh,
+                ..Default::default()
+            },
+        ];
+
+        #[rustfmt::skip]
+        let weights = [
+            // Hidden neuron 0: x0 - x1
+            0.0, 1.0, -1.0,
+            // Hidden neuron 1: always dead
+            -1.0, 0.0, 0.0,
+            // Output: tanh(h0 + h1)
+            0.0, 1.0, 1.0,
+        ];
+
+        Network::from_weights(&layers, weights)
+    }
+
+    #[test]
+    fn propagate_traced() {
+        let trace = network().propagate_traced(&[3.0, 1.0]);
+
+        assert_relative_eq!(trace.inputs.as_slice(), [3.0, 1.0].as_ref());
+
+        assert_relative_eq!(
+            trace.layers[0].pre_activation.as_slice(),
+            [2.0, -1.0].as_ref()
+        );
+
+        assert_relative_eq!(
+            trace.layers[0].post_activation.as_slice(),
+            [2.0, 0.0].as_ref()
+        );
+
+        assert_relative_eq!(trace.layers[1].pre_activation.as_slice(), [2.0].as_ref());
+        assert_relative_eq!(trace.outputs()[0], 2.0f32.tanh());
+        assert_eq!(trace.outputs(), network().propogate(vec![3.0, 1.0]));
+    }
+
+    #[test]
+    fn activation_stats() {
+        let inputs = [
+            3.0, 1.0, //
+            1.0, 3.0, //
+            2.0, 0.0, //
+            0.0, 0.0,
+        ];
+
+        let stats = network().activation_stats(&inputs);
+
+        assert_eq!(stats.samples, 4);
+
+        let hidden = &stats.layers[0];
+
+        // Hidden neuron 0 outputs 2, 0, 2 and 0 respectively
+        assert_eq!(hidden[0].inactive, 2);
+        assert_relative_eq!(hidden[0].min, 0.0);
+        assert_relative_eq!(hidden[0].max, 2.0);
+        assert_relative_eq!(hidden[0].mean, 1.0);
+
+        assert_eq!(hidden[1].inactive, 4);
+        assert_eq!(stats.dead_neurons(), [(0, 1)]);
+    }
+
+    #[test]
+    fn propagate_traced_starts_from_zero_state() {
+        let layers = [
+            LayerTopology {
+                neurons: 1,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Identity,
+                kind: LayerKind::Recurrent,
+                ..Default::default()
+            },
+        ];
+
+        let network = Network::from_weights(&layers, [0.0, 1.0, 1.0]);
+
+        let first = network.propagate_traced(&[1.0]);
+        let second = network.propagate_traced(&[1.0]);
+
+        assert_relative_eq!(first.outputs()[0], 1.0);
+        assert_relative_eq!(second.outputs()[0], 1.0);
+
+        let stats = network.activation_stats(&[1.0, 2.0]);
+
+        assert_relative_eq!(stats.layers[0][0].mean, 1.5);
+    }
+}
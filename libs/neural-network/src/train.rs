@@ -0,0 +1,710 @@
+use crate::*;
+
+/// How far network's outputs are from the expected ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Loss {
+    /// Mean of `(output - target)^2`
+    #[default]
+    MeanSquaredError,
+
+    /// Mean of `-(target * ln(output) + (1 - target) * ln(1 - output))`;
+    /// expects outputs and targets within `[0, 1]`, e.g. from a sigmoid
+    /// layer.
+    CrossEntropy,
+}
+
+impl Loss {
+    /// Loss averaged over all of the outputs.
+    pub fn apply(self, outputs: &[f32], targets: &[f32]) -> f32 {
+        assert_eq!(outputs.len(), targets.len());
+
+        if outputs.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f32 = outputs
+            .iter()
+            .zip(targets)
+            .map(|(&output, &target)| match self {
+                Self::MeanSquaredError => (output - target).powi(2),
+                Self::CrossEntropy => {
+                    let output = clamp_probability(output);
+
+                    -(target * output.ln() + (1.0 - target) * (1.0 - output).ln())
+                }
+            })
+            .sum();
+
+        sum / outputs.len() as f32
+    }
+
+    /// Derivative of a single output's term of [`Self::apply()`] (before
+    /// averaging) with respect to that output.
+    fn derivative(self, output: f32, target: f32) -> f32 {
+        match self {
+            Self::MeanSquaredError => 2.0 * (output - target),
+            Self::CrossEntropy => {
+                let output = clamp_probability(output);
+
+                (output - target) / (output * (1.0 - output))
+            }
+        }
+    }
+}
+
+/// Keeps cross-entropy finite for outputs of exactly `0` or `1`.
+fn clamp_probability(p: f32) -> f32 {
+    p.clamp(1e-7, 1.0 - 1e-7)
+}
+
+/// How gradients are turned into weight updates.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Optimizer {
+    /// Stochastic gradient descent, optionally with momentum (`0.0`
+    /// disables it)
+    Sgd { learning_rate: f32, momentum: f32 },
+
+    /// Adam - per-weight learning rates adapted from running averages of
+    /// the gradient and its square
+    Adam {
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+    },
+}
+
+impl Optimizer {
+    /// Plain SGD, without momentum.
+    pub fn sgd(learning_rate: f32) -> Self {
+        Self::Sgd {
+            learning_rate,
+            momentum: 0.0,
+        }
+    }
+
+    /// Adam with the commonly used `beta1 = 0.9`, `beta2 = 0.999` and
+    /// `epsilon = 1e-8`.
+    pub fn adam(learning_rate: f32) -> Self {
+        Self::Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+/// Supervised training of a [`Network`] using backpropagation.
+///
+/// Meant for pre-training a network (e.g. by imitating a hand-written
+/// policy) before handing its [`Network::weights()`] over to the genetic
+/// algorithm - training changes weights in place and never the topology.
+///
+/// Samples are independent of each other: recurrent layers start from zero
+/// state for each one and gradients don't flow back through time, so
+/// recurrent weights are left as they are.
+///
+/// Trainer holds optimizer's state (e.g. Adam's running averages), so a
+/// single trainer should be used with a single network.
+#[derive(Clone, Debug)]
+pub struct Trainer {
+    loss: Loss,
+    optimizer: Optimizer,
+    steps: i32,
+
+    /// Per parameter slice (see [`params_mut()`]): SGD's velocity or
+    /// Adam's first moment
+    first: Vec<Vec<f32>>,
+
+    /// Per parameter slice: Adam's second moment
+    second: Vec<Vec<f32>>,
+}
+
+impl Trainer {
+    pub fn new(loss: Loss, optimizer: Optimizer) -> Self {
+        Self {
+            loss,
+            optimizer,
+            steps: 0,
+            first: Vec::new(),
+            second: Vec::new(),
+        }
+    }
+
+    /// Performs a single optimizer step on `inputs` and `targets`, which
+    /// contain samples laid out one after another (as in
+    /// [`Network::propagate_batch()`]).
+    ///
+    /// Returns the loss from before the step.
+    pub fn train_batch(&mut self, network: &mut Network, inputs: &[f32], targets: &[f32]) -> f32 {
+        let (loss, gradients) = network.gradients(self.loss, inputs, targets);
+
+        self.step(network, &gradients);
+
+        loss
+    }
+
+    /// Goes once over the whole dataset in batches of `batch_size` samples
+    /// (the last one can be smaller), performing an optimizer step per
+    /// batch.
+    ///
+    /// Returns the average loss from before each step.
+    pub fn train_epoch(
+        &mut self,
+        network: &mut Network,
+        inputs: &[f32],
+        targets: &[f32],
+        batch_size: usize,
+    ) -> f32 {
+        assert!(batch_size > 0, "batch size must be positive");
+
+        let samples = network.count_samples(inputs, targets);
+        let input_size = network.layers[0].input_size;
+        let output_size = network.output_size();
+
+        let mut total = 0.0;
+        let mut count = 0;
+
+        for start in (0..samples).step_by(batch_size) {
+            let end = (start + batch_size).min(samples);
+
+            total += self.train_batch(
+                network,
+                &inputs[start * input_size..end * input_size],
+                &targets[start * output_size..end * output_size],
+            );
+
+            count += 1;
+        }
+
+        if count == 0 {
+            0.0
+        } else {
+            total / count as f32
+        }
+    }
+
+    fn step(&mut self, network: &mut Network, gradients: &[Vec<f32>]) {
+        if self.first.is_empty() {
+            self.first = gradients.iter().map(|g| vec![0.0; g.len()]).collect();
+            self.second = self.first.clone();
+        }
+
+        self.steps += 1;
+
+        let slices = params_mut(network)
+            .zip(gradients)
+            .zip(self.first.iter_mut().zip(&mut self.second));
+
+        for ((params, gradients), (first, second)) in slices {
+            assert_eq!(
+                params.len(),
+                first.len(),
+                "trainer was used with a different network"
+            );
+
+            for (i, param) in params.iter_mut().enumerate() {
+                let gradient = gradients[i];
+
+                match self.optimizer {
+                    Optimizer::Sgd {
+                        learning_rate,
+                        momentum,
+                    } => {
+                        first[i] = momentum * first[i] - learning_rate * gradient;
+                        *param += first[i];
+                    }
+
+                    Optimizer::Adam {
+                        learning_rate,
+                        beta1,
+                        beta2,
+                        epsilon,
+                    } => {
+                        first[i] = beta1 * first[i] + (1.0 - beta1) * gradient;
+                        second[i] = beta2 * second[i] + (1.0 - beta2) * gradient.powi(2);
+
+                        let first = first[i] / (1.0 - beta1.powi(self.steps));
+                        let second = second[i] / (1.0 - beta2.powi(self.steps));
+
+                        *param -= learning_rate * first / (second.sqrt() + epsilon);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Network's trainable numbers, as slices: for each layer its weights,
+/// biases and - for recurrent layers - recurrent weights.
+fn params_mut(network: &mut Network) -> impl Iterator<Item = &mut [f32]> {
+    network.layers.iter_mut().flat_map(|layer| {
+        let recurrent = layer
+            .recurrence
+            .as_mut()
+            .map(|recurrence| recurrence.weights.as_mut_slice());
+
+        [
+            Some(layer.weights.as_mut_slice()),
+            Some(layer.biases.as_mut_slice()),
+            recurrent,
+        ]
+        .into_iter()
+        .flatten()
+    })
+}
+
+impl Network {
+    /// Number of samples laid out one after another in `inputs`, checking
+    /// that `targets` holds as many.
+    fn count_samples(&self, inputs: &[f32], targets: &[f32]) -> usize {
+        let input_size = self.layers[0].input_size;
+        let output_size = self.output_size();
+
+        assert!(input_size > 0, "cannot batch inputs of a zero-sized layer");
+        assert_eq!(
+            inputs.len() % input_size,
+            0,
+            "got {} inputs, which is not a multiple of input size {}",
+            inputs.len(),
+            input_size
+        );
+
+        let samples = inputs.len() / input_size;
+
+        assert_eq!(
+            targets.len(),
+            samples * output_size,
+            "got {} targets for {} samples of {} outputs",
+            targets.len(),
+            samples,
+            output_size
+        );
+
+        samples
+    }
+
+    /// Returns loss over given samples and its gradient with respect to
+    /// each of the parameters, laid out as in [`params_mut()`].
+    pub(crate) fn gradients(
+        &self,
+        loss: Loss,
+        inputs: &[f32],
+        targets: &[f32],
+    ) -> (f32, Vec<Vec<f32>>) {
+        self.count_samples(inputs, targets);
+
+        let input_size = self.layers[0].input_size;
+        let output_size = self.output_size();
+
+        let mut gradients: Vec<Vec<f32>> = self
+            .layers
+            .iter()
+            .flat_map(|layer| {
+                let recurrent = layer
+                    .recurrence
+                    .as_ref()
+                    .map(|recurrence| vec![0.0; recurrence.weights.len()]);
+
+                [
+                    Some(vec![0.0; layer.weights.len()]),
+                    Some(vec![0.0; layer.biases.len()]),
+                    recurrent,
+                ]
+                .into_iter()
+                .flatten()
+            })
+            .collect();
+
+        let mut total = 0.0;
+
+        // Loss is averaged over all outputs of all samples
+        let scale = 1.0 / targets.len().max(1) as f32;
+
+        for (inputs, targets) in inputs
+            .chunks_exact(input_size)
+            .zip(targets.chunks_exact(output_size.max(1)))
+        {
+            let trace = self.trace(inputs);
+
+            total += loss.apply(trace.outputs(), targets) * targets.len() as f32;
+
+            let last = self.layers.len() - 1;
+
+            // dLoss / dPreActivation of the current layer's neurons
+            let mut deltas: Vec<f32> = trace
+                .outputs()
+                .iter()
+                .zip(targets)
+                .map(|(&output, &target)| scale * loss.derivative(output, target))
+                .collect();
+
+            self.layers[last].backpropagate(
+                &trace.layers[last].pre_activation,
+                &trace.layers[last].post_activation,
+                &mut deltas,
+            );
+
+            let mut slice = gradients.len();
+
+            for (idx, layer) in self.layers.iter().enumerate().rev() {
+                let layer_inputs = if idx == 0 {
+                    inputs
+                } else {
+                    &trace.layers[idx - 1].post_activation
+                };
+
+                // Recurrent weights only ever see zero state, so their
+                // gradients stay zero
+                if layer.recurrence.is_some() {
+                    slice -= 1;
+                }
+
+                slice -= 2;
+
+                add_outer(&mut gradients[slice], &deltas, layer_inputs);
+
+                for (gradient, delta) in gradients[slice + 1].iter_mut().zip(&deltas) {
+                    *gradient += delta;
+                }
+
+                if idx > 0 {
+                    let previous = &self.layers[idx - 1];
+
+                    deltas = (0..layer.input_size)
+                        .map(|input| {
+                            deltas
+                                .iter()
+                                .enumerate()
+                                .map(|(neuron, delta)| layer.row(neuron)[input] * delta)
+                                .sum()
+                        })
+                        .collect();
+
+                    previous.backpropagate(
+                        &trace.layers[idx - 1].pre_activation,
+                        &trace.layers[idx - 1].post_activation,
+                        &mut deltas,
+                    );
+                }
+            }
+        }
+
+        (total * scale, gradients)
+    }
+}
+
+/// `matrix += column * row^T`, where `matrix` is row-major with one row
+/// per element of `column`.
+fn add_outer(matrix: &mut [f32], column: &[f32], row: &[f32]) {
+    if row.is_empty() {
+        return;
+    }
+
+    for (matrix_row, &c) in matrix.chunks_exact_mut(row.len()).zip(column) {
+        for (m, &r) in matrix_row.iter_mut().zip(row) {
+            *m += c * r;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network(layers: &[LayerTopology]) -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(&mut rng, layers)
+    }
+
+    fn topology(hidden: Activation, output: Activation) -> [LayerTopology; 3] {
+        [
+            LayerTopology {
+                neurons: 2,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 3,
+                activation: hidden,
+                ..Default::default()
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: output,
+                ..Default::default()
+            },
+        ]
+    }
+
+    mod loss {
+        use super::*;
+
+        #[test]
+        fn mean_squared_error() {
+            let actual = Loss::MeanSquaredError.apply(&[1.0, 0.0], &[0.0, 2.0]);
+
+            assert_relative_eq!(actual, 2.5);
+        }
+
+        #[test]
+        fn cross_entropy() {
+            let actual = Loss::CrossEntropy.apply(&[0.5, 0.9], &[1.0, 1.0]);
+
+            assert_relative_eq!(actual, -(0.5f32.ln() + 0.9f32.ln()) / 2.0);
+        }
+
+        #[test]
+        fn cross_entropy_is_finite_for_saturated_outputs() {
+            let actual = Loss::CrossEntropy.apply(&[0.0, 1.0], &[1.0, 0.0]);
+
+            assert!(actual.is_finite());
+        }
+    }
+
+    mod gradients {
+        use super::*;
+
+        /// Compares backpropagated gradients with ones estimated by
+        /// nudging each of the parameters.
+        fn check(mut network: Network, loss: Loss) {
+            let inputs = [0.5, -0.3, -0.8, 0.1, 0.2, 0.9];
+            let targets = [0.2, 0.7, 0.9, 0.1, 0.4, 0.6];
+
+            let (_, actual) = network.gradients(loss, &inputs, &targets);
+
+            let loss_of = |network: &Network| {
+                let mut scratch = Scratch::default();
+                let outputs = network.propagate_samples(&inputs, &mut scratch);
+
+                loss.apply(outputs, &targets)
+            };
+
+            let h = 1e-3;
+
+            for (slice, actual) in actual.iter().enumerate() {
+                for (param, &actual) in actual.iter().enumerate() {
+                    params_mut(&mut network).nth(slice).unwrap()[param] += h;
+                    let above = loss_of(&network);
+
+                    params_mut(&mut network).nth(slice).unwrap()[param] -= 2.0 * h;
+                    let below = loss_of(&network);
+
+                    params_mut(&mut network).nth(slice).unwrap()[param] += h;
+
+                    let expected = (above - below) / (2.0 * h);
+
+                    assert_relative_eq!(actual, expected, epsilon = 2e-3);
+                }
+            }
+        }
+
+        #[test]
+        fn mean_squared_error() {
+            check(
+                network(&topology(Activation::Tanh, Activation::Identity)),
+                Loss::MeanSquaredError,
+            );
+        }
+
+        #[test]
+        fn cross_entropy() {
+            check(
+                network(&topology(Activation::Softsign, Activation::Sigmoid)),
+                Loss::CrossEntropy,
+            );
+        }
+
+        #[test]
+        fn softmax() {
+            check(
+                network(&topology(Activation::Softmax, Activation::Softmax)),
+                Loss::CrossEntropy,
+            );
+        }
+
+        #[test]
+        fn recurrent() {
+            let mut layers = topology(Activation::Tanh, Activation::Sigmoid);
+
+            layers[1].kind = LayerKind::Recurrent;
+
+            check(network(&layers), Loss::MeanSquaredError);
+        }
+
+        #[test]
+        fn loss() {
+            let network = network(&topology(Activation::Tanh, Activation::Identity));
+            let inputs = [0.5, -0.3, -0.8, 0.1];
+            let targets = [0.2, 0.7, 0.9, 0.1];
+
+            let (actual, _) = network.gradients(Loss::MeanSquaredError, &inputs, &targets);
+
+            let mut scratch = Scratch::default();
+            let outputs = network.propagate_batch(&inputs, &mut scratch);
+
+            assert_relative_eq!(actual, Loss::MeanSquaredError.apply(outputs, &targets));
+        }
+    }
+
+    mod trainer {
+        use super::*;
+
+        /// Trains network for given number of epochs, returning the final
+        /// loss.
+        fn train(
+            network: &mut Network,
+            trainer: &mut Trainer,
+            inputs: &[f32],
+            targets: &[f32],
+            epochs: usize,
+        ) -> f32 {
+            for _ in 0..epochs {
+                trainer.train_epoch(network, inputs, targets, 2);
+            }
+
+            let mut scratch = Scratch::default();
+            let outputs = network.propagate_batch(inputs, &mut scratch);
+
+            trainer.loss.apply(outputs, targets)
+        }
+
+        #[test]
+        fn sgd_learns_linear_function() {
+            let mut network = network(&[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    activation: Activation::Identity,
+                    ..Default::default()
+                },
+            ]);
+
+            let mut trainer = Trainer::new(Loss::MeanSquaredError, Optimizer::sgd(0.1));
+
+            // y = 2a - b + 0.5
+            let inputs = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.5, -0.5];
+            let targets = [0.5, 2.5, -0.5, 1.5, 2.0];
+
+            let loss = train(&mut network, &mut trainer, &inputs, &targets, 500);
+
+            assert!(loss < 1e-6, "loss = {loss}");
+
+            let actual = network.propogate(vec![-1.0, 2.0]);
+
+            assert_relative_eq!(actual[0], -3.5, epsilon = 1e-2);
+        }
+
+        #[test]
+        #[should_panic(expected = "got 3 targets for 2 samples of 1 outputs")]
+        fn train_epoch_with_extra_targets() {
+            let mut network = network(&[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    ..Default::default()
+                },
+            ]);
+
+            let mut trainer = Trainer::new(Loss::MeanSquaredError, Optimizer::sgd(0.1));
+
+            trainer.train_epoch(&mut network, &[0.0, 0.0, 1.0, 1.0], &[0.0, 1.0, 2.0], 2);
+        }
+
+        #[test]
+        fn sgd_with_momentum_converges_faster() {
+            let layers = topology(Activation::Tanh, Activation::Tanh);
+            let inputs = [0.5, -0.3, -0.8, 0.1, 0.2, 0.9, -0.4, -0.4];
+            let targets = [0.2, -0.7, 0.9, 0.1, -0.4, 0.6, 0.0, 0.3];
+
+            let without = train(
+                &mut network(&layers),
+                &mut Trainer::new(Loss::MeanSquaredError, Optimizer::sgd(0.05)),
+                &inputs,
+                &targets,
+                100,
+            );
+
+            let with = train(
+                &mut network(&layers),
+                &mut Trainer::new(
+                    Loss::MeanSquaredError,
+                    Optimizer::Sgd {
+                        learning_rate: 0.05,
+                        momentum: 0.9,
+                    },
+                ),
+                &inputs,
+                &targets,
+                100,
+            );
+
+            assert!(with < without, "with = {with}, without = {without}");
+        }
+
+        #[test]
+        fn adam_learns_xor() {
+            let mut network = network(&[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 4,
+                    activation: Activation::Tanh,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    activation: Activation::Sigmoid,
+                    ..Default::default()
+                },
+            ]);
+
+            let mut trainer = Trainer::new(Loss::CrossEntropy, Optimizer::adam(0.05));
+
+            let inputs = [0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0];
+            let targets = [0.0, 1.0, 1.0, 0.0];
+
+            let loss = train(&mut network, &mut trainer, &inputs, &targets, 500);
+
+            assert!(loss < 0.05, "loss = {loss}");
+
+            for (inputs, &target) in inputs.chunks(2).zip(&targets) {
+                let actual = network.propogate(inputs.to_vec());
+
+                assert_relative_eq!(actual[0], target, epsilon = 0.1);
+            }
+        }
+
+        #[test]
+        fn trained_weights_round_trip() {
+            let layers = topology(Activation::Tanh, Activation::Tanh);
+            let mut network = network(&layers);
+            let mut trainer = Trainer::new(Loss::MeanSquaredError, Optimizer::adam(0.01));
+
+            trainer.train_batch(&mut network, &[0.5, -0.5], &[0.3, -0.3]);
+
+            // ... so that trained network can be handed over to the genetic
+            // algorithm as a chromosome
+            let restored = Network::from_weights(&layers, network.weights());
+
+            assert_eq!(
+                restored.propogate(vec![0.1, 0.2]),
+                network.propogate(vec![0.1, 0.2])
+            );
+        }
+    }
+}
@@ -30,12 +30,22 @@ impl Simulation {
         let stats = self.sim.train(&mut self.rng);
 
         format!(
-            "min={:.2}, max={:.2}, avg={:.2}",
-            stats.min_fitness, stats.max_fitness, stats.avg_fitness
+            "min={:.2}, max={:.2}, avg={:.2}, median={:.2}, diversity={:.3}",
+            stats.min_fitness,
+            stats.max_fitness,
+            stats.avg_fitness,
+            stats.median_fitness,
+            stats.diversity
         )
     }
 }
 
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
 pub struct World {
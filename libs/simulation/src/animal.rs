@@ -42,6 +42,10 @@ impl Animal {
         self.rotation
     }
 
+    pub fn brain(&self) -> &Brain {
+        &self.brain
+    }
+
     fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
         Self {
             position: rng.gen(),
@@ -6,6 +6,9 @@ pub struct Brain {
 }
 
 impl Brain {
+    /// Name of the output head driving the bird's speed and rotation.
+    pub const STEERING: &'static str = "steering";
+
     pub fn random(rng: &mut dyn RngCore, eye: &Eye) -> Self {
         Self {
             nn: nn::Network::random(rng, &Self::topology(eye)),
@@ -22,15 +25,42 @@ impl Brain {
         self.nn.weights().collect()
     }
 
+    /// Underlying network, e.g. for comparing brains with
+    /// [`nn::Network::weight_distance()`].
+    pub fn network(&self) -> &nn::Network {
+        &self.nn
+    }
+
+    /// Part of the network's outputs driving the bird's speed and
+    /// rotation, in that order.
+    pub(crate) fn steering<'a>(&self, outputs: &'a [f32]) -> &'a [f32] {
+        self.nn
+            .head(outputs, Self::STEERING)
+            .expect("brain should have a steering head")
+    }
+
     fn topology(eye: &Eye) -> [nn::LayerTopology; 3] {
         [
             nn::LayerTopology {
                 neurons: eye.cells(),
+                ..Default::default()
             },
             nn::LayerTopology {
                 neurons: 2 * eye.cells(),
+                activation: nn::Activation::Relu,
+                ..Default::default()
+            },
+            // Tanh lets the outputs go negative, so birds can slow down
+            // and turn both ways
+            nn::LayerTopology {
+                neurons: 2,
+                heads: vec![nn::OutputHead::new(
+                    Self::STEERING,
+                    2,
+                    nn::Activation::Tanh,
+                )],
+                ..Default::default()
             },
-            nn::LayerTopology { neurons: 2 },
         ]
     }
 }
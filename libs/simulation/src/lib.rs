@@ -44,21 +44,34 @@ const GENERATION_LENGTH: usize = 2500;
 
 pub struct Simulation {
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    ga: ga::GeneticAlgorithm<ga::SelectionConfig>,
     age: usize,
+    /// Buffers shared by all brains, so that thinking doesn't allocate
+    scratch: nn::Scratch,
+    /// Every bird's vision, one after another
+    vision: Vec<f32>,
 }
 
 impl Simulation {
     pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world = World::random(rng);
-
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection,
-            ga::UniformCrossover,
-            ga::GaussianMutation::new(0.01, 0.3),
-        );
+        Self::with_config(rng, &ga::GaConfig::default())
+    }
 
-        Self { world, ga, age: 0 }
+    /// Like [`Self::random()`], but evolving birds with given settings
+    /// (e.g. loaded with [`ga::GaConfig::from_toml()`]).
+    ///
+    /// Panics if the config is invalid.
+    pub fn with_config(rng: &mut dyn RngCore, config: &ga::GaConfig) -> Self {
+        let world = World::random(rng);
+        let ga = config.build();
+
+        Self {
+            world,
+            ga,
+            age: 0,
+            scratch: nn::Scratch::new(),
+            vision: Vec::new(),
+        }
     }
 
     pub fn world(&self) -> &World {
@@ -103,17 +116,43 @@ impl Simulation {
     }
 
     fn process_brains(&mut self) {
-        for animal in &mut self.world.animals {
-            let vision =
-                animal
-                    .eye
-                    .process_vision(animal.position, animal.rotation, &self.world.foods);
+        let Self {
+            world,
+            scratch,
+            vision,
+            ..
+        } = self;
+
+        vision.clear();
+
+        for animal in &world.animals {
+            vision.extend(animal.eye.process_vision(
+                animal.position,
+                animal.rotation,
+                &world.foods,
+            ));
+        }
+
+        let responses = nn::Network::propagate_many(
+            world.animals.iter().map(|animal| &animal.brain.nn),
+            vision,
+            scratch,
+        );
+
+        // All brains share the same topology, so each one gets an equal
+        // share of the outputs
+        let response_size = responses.len() / world.animals.len().max(1);
 
-            let response = animal.brain.nn.propogate(vision);
+        for (animal, response) in world
+            .animals
+            .iter_mut()
+            .zip(responses.chunks_exact(response_size.max(1)))
+        {
+            let steering = animal.brain.steering(response);
 
             // Limit number to ranges
-            let speed = response[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
-            let rotation = response[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+            let speed = steering[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
+            let rotation = steering[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
 
             animal.speed = (animal.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
             animal.rotation = na::Rotation2::new(animal.rotation.angle() + rotation);